@@ -0,0 +1,79 @@
+//! Headless benchmark of test generation and typing, for tracking performance
+//! Called specially as a hidden argument
+
+use crate::{
+    config::{Config, TestCfg},
+    lang::Lang,
+    test::TestBuilder,
+    ui::{Styles, Ui},
+};
+use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::{
+    hint::black_box,
+    path::PathBuf,
+    sync::mpsc::sync_channel,
+    time::{Duration, Instant},
+};
+
+/// Generate and perfectly type `tests` tests of `words` words each, printing time spent in each stage.
+pub fn run(lang: &str, dirs: &[PathBuf], tests: usize, words: usize) -> std::io::Result<()> {
+    let lang = Lang::get_by_name(lang, dirs)?;
+    let (tx, _rx) = sync_channel(1);
+    let mut t = TestBuilder::new(Styles::default(), tx)
+        .cfg(TestCfg {
+            // never send requests, nothing is listening
+            freeze_on_complete: true,
+            ..Default::default()
+        })
+        .build();
+    t.set_punctuated(lang.punctuated);
+
+    let (mut gen_t, mut type_t, mut metric_t) = (Duration::ZERO, Duration::ZERO, Duration::ZERO);
+    let mut ui_t = Duration::ZERO;
+    let mut keys = 0;
+    for seed in 0..tests as u64 {
+        let now = Instant::now();
+        t.test_from(lang.gen_words(words, seed));
+        gen_t += now.elapsed();
+
+        // the same words handed to a whole ui, as when embedded
+        let words = t.words().map(String::from).collect::<Vec<String>>();
+        let now = Instant::now();
+        black_box(Ui::with_words(Config::default(), words));
+        ui_t += now.elapsed();
+
+        let input = t.words().collect::<Vec<&str>>().join(" ") + " ";
+        let now = Instant::now();
+        for c in input.chars() {
+            // further keys would request the results screen
+            if t.is_completed() {
+                break;
+            }
+            t.handle_events(KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE));
+            keys += 1;
+        }
+        type_t += now.elapsed();
+
+        let now = Instant::now();
+        black_box((t.correct_chars(), t.elapsed_secs(), t.errors()));
+        metric_t += now.elapsed();
+    }
+
+    println!(
+        "Benchmarked {tests} tests of {words} words from {} ({keys} keypresses)",
+        lang.name
+    );
+    for (stage, d) in [
+        ("generation", gen_t),
+        ("typing", type_t),
+        ("metrics", metric_t),
+        ("ui setup", ui_t),
+    ] {
+        println!(
+            "  {stage:<10} {:>10.3} ms total {:>10.3} us/test",
+            d.as_secs_f64() * 1e3,
+            d.as_secs_f64() * 1e6 / tests.max(1) as f64
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,108 @@
+//! Calibration of a baseline speed over several tests, and the speed goal it can set
+use crate::{results, test::TestMetrics};
+use serde::{Deserialize, Serialize};
+use std::{io, path::PathBuf};
+
+/// Baseline speed and accuracy to beat, persisted under the data dir.
+#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, Debug)]
+pub struct Goal {
+    pub wpm: f64,
+    /// Percentage of correct keypresses
+    pub accuracy: f64,
+}
+
+impl Goal {
+    /// Path to the goal file.
+    pub fn path() -> PathBuf {
+        results::dir().join("goal.json")
+    }
+
+    /// Load the goal, None if none has been set.
+    pub fn load() -> io::Result<Option<Self>> {
+        results::load_json(&Self::path())
+    }
+
+    /// Save the goal.
+    pub fn save(&self) -> io::Result<()> {
+        results::save_json(&Self::path(), self)
+    }
+}
+
+/// A run of back-to-back tests, aggregated into a baseline once all are complete.
+pub struct Calibration {
+    tests: usize,
+    metrics: Vec<TestMetrics>,
+}
+
+impl Calibration {
+    /// Calibrate over `tests` tests, at least one
+    pub fn new(tests: usize) -> Self {
+        Self {
+            tests: tests.max(1),
+            metrics: Vec::new(),
+        }
+    }
+
+    /// Add the metrics of a completed test
+    pub fn push(&mut self, m: TestMetrics) {
+        self.metrics.push(m);
+    }
+
+    /// Number of the test in progress and the total, such as (2, 5)
+    pub fn progress(&self) -> (usize, usize) {
+        ((self.metrics.len() + 1).min(self.tests), self.tests)
+    }
+
+    /// All tests have been completed
+    pub fn is_done(&self) -> bool {
+        self.metrics.len() >= self.tests
+    }
+
+    /// Baseline from every test so far, weighting each by its length. None before any are complete
+    pub fn baseline(&self) -> Option<Goal> {
+        let total = self.metrics.iter().cloned().reduce(|mut a, b| {
+            a += b;
+            a
+        })?;
+        Some(Goal {
+            wpm: total.net_wpm(),
+            accuracy: total.accuracy(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn metrics(correct_chars: usize, char_presses: usize, errors: usize, secs: f64) -> TestMetrics {
+        TestMetrics {
+            correct_chars,
+            char_presses,
+            errors,
+            secs,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_baseline() {
+        let mut c = Calibration::new(2);
+        assert_eq!(c.baseline(), None);
+        assert_eq!(c.progress(), (1, 2));
+
+        c.push(metrics(50, 50, 0, 10.0));
+        assert!(!c.is_done());
+        c.push(metrics(50, 50, 10, 30.0));
+        assert!(c.is_done());
+        assert_eq!(c.progress(), (2, 2));
+        // 100 chars over 40 secs
+        assert_eq!(
+            c.baseline(),
+            Some(Goal {
+                wpm: 30.0,
+                accuracy: 90.0
+            })
+        );
+    }
+}
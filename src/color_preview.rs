@@ -90,19 +90,17 @@ impl ColorPreview {
     }
 
     fn handle_events(&mut self) -> std::io::Result<()> {
-        if let Event::Key(key) = event::read()? {
-            if key.kind == KeyEventKind::Press {
-                match key.code {
-                    KeyCode::Char('q') | KeyCode::Esc => self.state = State::Stopped,
-                    KeyCode::Char('c') => {
-                        if key.modifiers.contains(KeyModifiers::CONTROL) {
-                            self.state = State::Stopped
-                        }
-                    }
-                    KeyCode::Left => self.select_prev_palette(),
-                    KeyCode::Right => self.select_next_palette(),
-                    _ => {}
+        if let Event::Key(key) = event::read()?
+            && key.kind == KeyEventKind::Press
+        {
+            match key.code {
+                KeyCode::Char('q') | KeyCode::Esc => self.state = State::Stopped,
+                KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    self.state = State::Stopped
                 }
+                KeyCode::Left => self.select_prev_palette(),
+                KeyCode::Right => self.select_next_palette(),
+                _ => {}
             }
         }
         Ok(())
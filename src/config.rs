@@ -1,12 +1,18 @@
 //! TOML configuration and CLI arguments
-use crate::lang::Lang;
+use crate::bench;
 use crate::color_preview::ColorPreview;
+use crate::lang::Lang;
+use crate::practice::Practice;
+use crate::results::SpeedUnit;
+use crate::test::{TestAlignment, parse_key, types_text};
+use crate::ui::CaretStyle;
 use clap::Parser;
 use ratatui::style::Color;
 use serde::{Deserialize, Serialize};
 use std::{
     fs::{self, File},
     io::{self, Write},
+    path::{Path, PathBuf},
     process,
 };
 
@@ -16,19 +22,57 @@ use std::{
 pub struct Config {
     /// Test language
     pub lang: String,
+    /// Languages cycled through with <F6>, for quickly switching between a few
+    pub favorites: Vec<String>,
+    /// Extra directories to find languages in, after the default one.
+    /// Languages of the same name in earlier directories are preferred
+    pub lang_paths: Vec<PathBuf>,
+    /// Label attached to recorded results, for organizing practice sessions
+    pub label: String,
     pub theme: ThemeCfg,
     /// How many words to test for
     pub word_count: u32,
     pub ui: UiCfg,
+    pub test: TestCfg,
+    pub stats: StatsCfg,
+    pub results: ResultsCfg,
+    pub keybinds: KeybindsCfg,
+    /// Seed for the words of the first test, to replay a previous test. CLI only
+    #[serde(skip)]
+    pub seed: Option<u64>,
+    /// Options given on the CLI, layered over the config file whenever it is read
+    #[serde(skip)]
+    overrides: Overrides,
+}
+
+/// Config options given on the CLI, each unset if it wasn't given
+#[derive(Default, Clone)]
+struct Overrides {
+    lang: Option<String>,
+    word_count: Option<u32>,
+    label: Option<String>,
+    top_words: Option<usize>,
+    practice: bool,
+    nemesis: bool,
+    zen: bool,
 }
 
 impl Default for Config {
     fn default() -> Self {
         Self {
             lang: "english".to_string(),
+            favorites: Vec::new(),
+            lang_paths: Vec::new(),
+            label: "".to_string(),
             word_count: 50,
             theme: ThemeCfg::default(),
             ui: UiCfg::default(),
+            test: TestCfg::default(),
+            stats: StatsCfg::default(),
+            results: ResultsCfg::default(),
+            keybinds: KeybindsCfg::default(),
+            seed: None,
+            overrides: Overrides::default(),
         }
     }
 }
@@ -37,9 +81,11 @@ impl Config {
     /// Safely get the configuration from config directory, using defaults if it cannot be accessed.
     /// Then load CLI arguments and layer those options over the TOML configuration.
     /// Also creates a new default config if none exists at runtime.
+    ///
+    /// `arstyper.json` is read instead of `arstyper.toml` if it exists.
     pub fn get() -> std::io::Result<Self> {
         let a = Args::get()?;
-        let p = dirs::config_local_dir().unwrap().join("arstyper.toml");
+        let p = Self::path();
         let s = fs::read_to_string(&p).unwrap_or_else(|e| {
             println!(
                 "Error reading from {}: {e}\nAttempting to create default config...",
@@ -64,26 +110,100 @@ impl Config {
             "".to_string() // faster than returning c since its defaults anyways
         });
 
-        let mut cfg: Self = toml::from_str(&s).unwrap_or_else(|e| {
+        let mut cfg = Self::parse(&p, &s).unwrap_or_else(|e| {
             println!("{}: {e}", p.display());
             process::exit(0b1);
         });
 
         // layer args over cfg
-        if let Some(l) = a.lang {
-            cfg.lang = l;
+        cfg.seed = a.seed;
+        cfg.overrides = Overrides {
+            lang: a.lang,
+            word_count: a.words,
+            label: a.label,
+            top_words: a.top,
+            practice: a.practice,
+            nemesis: a.nemesis,
+            zen: a.zen,
+        };
+        cfg.apply_overrides();
+
+        Ok(cfg)
+    }
+
+    /// Layer the options given on the CLI over those from the file
+    fn apply_overrides(&mut self) {
+        let o = self.overrides.clone();
+        if let Some(l) = o.lang {
+            self.lang = l;
+        }
+        if let Some(wc) = o.word_count {
+            self.word_count = wc;
+        }
+        if let Some(l) = o.label {
+            self.label = l;
+        }
+        if let Some(n) = o.top_words {
+            self.test.top_words = n;
         }
-        if let Some(wc) = a.words {
-            cfg.word_count = wc;
+        self.test.practice |= o.practice;
+        self.test.nemesis |= o.nemesis;
+        self.test.zen |= o.zen;
+    }
+
+    /// Path to the config file, `arstyper.json` if it exists, otherwise `arstyper.toml`
+    pub fn path() -> PathBuf {
+        let dir = dirs::config_local_dir().unwrap();
+        let json = dir.join("arstyper.json");
+        if json.is_file() {
+            json
+        } else {
+            dir.join("arstyper.toml")
         }
+    }
 
+    /// Directories languages are found in, in order of preference
+    pub fn lang_dirs(&self) -> Vec<PathBuf> {
+        let mut dirs = vec![Lang::path()];
+        dirs.extend(self.lang_paths.iter().cloned());
+        dirs
+    }
+
+    /// Language directories of the config file, for endpoints that run before it is loaded.
+    /// Only the default directory if the config can't be read
+    fn file_lang_dirs() -> Vec<PathBuf> {
+        let p = Self::path();
+        fs::read_to_string(&p)
+            .ok()
+            .and_then(|s| Self::parse(&p, &s).ok())
+            .unwrap_or_default()
+            .lang_dirs()
+    }
+
+    /// Read the config file again. Options given on the CLI still apply over it, for the whole session.
+    pub fn reload(&self) -> Result<Self, String> {
+        let p = Self::path();
+        let s = fs::read_to_string(&p).map_err(|e| format!("{}: {e}", p.display()))?;
+        let mut cfg = Self::parse(&p, &s)?;
+        cfg.overrides = self.overrides.clone();
+        cfg.apply_overrides();
         Ok(cfg)
     }
+
+    /// Parse config contents as JSON or TOML, depending on the file extension.
+    fn parse(p: &Path, s: &str) -> Result<Self, String> {
+        if p.extension().is_some_and(|e| e == "json") {
+            // serde_json errors include the line and column
+            serde_json::from_str(s).map_err(|e| e.to_string())
+        } else {
+            toml::from_str(s).map_err(|e| e.to_string())
+        }
+    }
 }
 
 #[derive(Deserialize, Serialize)]
-#[serde(default)]
-/// Theme configuration, currently only supports named colors.
+#[serde(from = "RawThemeCfg")]
+/// Theme configuration. Colors are named, such as `"LightBlue"`, hex, such as `"#1e1e2e"`, or indexed, such as `"208"`.
 pub struct ThemeCfg {
     pub fg: Color,
     pub bg: Color,
@@ -91,6 +211,47 @@ pub struct ThemeCfg {
     pub typed_text: Color,
     pub incorrect_text: Color,
     pub accent: Color,
+    /// Colors that couldn't be parsed and were left as the default, such as "fg = `#12345`"
+    #[serde(skip)]
+    pub invalid: Vec<String>,
+}
+
+/// Theme as written in the config, before parsing colors
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct RawThemeCfg {
+    fg: Option<String>,
+    bg: Option<String>,
+    untyped_text: Option<String>,
+    typed_text: Option<String>,
+    incorrect_text: Option<String>,
+    accent: Option<String>,
+}
+
+impl From<RawThemeCfg> for ThemeCfg {
+    /// Parse colors, keeping defaults for any that are invalid so a typo can't stop startup
+    fn from(raw: RawThemeCfg) -> Self {
+        let mut t = Self::default();
+        let mut invalid = Vec::new();
+        for (name, s, c) in [
+            ("fg", raw.fg, &mut t.fg),
+            ("bg", raw.bg, &mut t.bg),
+            ("untyped_text", raw.untyped_text, &mut t.untyped_text),
+            ("typed_text", raw.typed_text, &mut t.typed_text),
+            ("incorrect_text", raw.incorrect_text, &mut t.incorrect_text),
+            ("accent", raw.accent, &mut t.accent),
+        ] {
+            let Some(s) = s else {
+                continue;
+            };
+            match s.parse::<Color>() {
+                Ok(parsed) => *c = parsed,
+                Err(_) => invalid.push(format!("{name} = `{s}`")),
+            }
+        }
+        t.invalid = invalid;
+        t
+    }
 }
 
 impl Default for ThemeCfg {
@@ -102,10 +263,128 @@ impl Default for ThemeCfg {
             typed_text: Color::DarkGray,
             incorrect_text: Color::Red,
             accent: Color::Magenta,
+            invalid: Vec::new(),
         }
     }
 }
 
+/// Minimum contrast ratio between text and background before warning, well below accessibility
+/// guidelines so only nearly invisible text is caught
+const MIN_CONTRAST: f64 = 2.0;
+
+/// How far the background is moved toward the foreground for shading
+const SHADE: f64 = 0.12;
+
+impl ThemeCfg {
+    /// Built-in themes by name, starting with the default
+    pub fn builtin() -> Vec<(&'static str, Self)> {
+        vec![
+            ("default", Self::default()),
+            (
+                "light",
+                Self {
+                    fg: Color::Black,
+                    bg: Color::White,
+                    untyped_text: Color::Black,
+                    typed_text: Color::DarkGray,
+                    incorrect_text: Color::Red,
+                    accent: Color::Blue,
+                    invalid: Vec::new(),
+                },
+            ),
+            (
+                "ocean",
+                Self {
+                    fg: Color::Rgb(192, 202, 245),
+                    bg: Color::Rgb(26, 27, 38),
+                    untyped_text: Color::Rgb(192, 202, 245),
+                    typed_text: Color::Rgb(86, 95, 137),
+                    incorrect_text: Color::Rgb(247, 118, 142),
+                    accent: Color::Rgb(122, 162, 247),
+                    invalid: Vec::new(),
+                },
+            ),
+            (
+                "forest",
+                Self {
+                    fg: Color::Rgb(211, 198, 170),
+                    bg: Color::Rgb(45, 53, 59),
+                    untyped_text: Color::Rgb(211, 198, 170),
+                    typed_text: Color::Rgb(133, 146, 137),
+                    incorrect_text: Color::Rgb(230, 126, 128),
+                    accent: Color::Rgb(167, 192, 128),
+                    invalid: Vec::new(),
+                },
+            ),
+        ]
+    }
+
+    /// Background moved slightly toward the foreground, for subtle shading.
+    /// None if either has no known RGB value
+    pub fn shade(&self) -> Option<Color> {
+        let ((br, bg, bb), (fr, fg, fb)) = (rgb(self.bg)?, rgb(self.fg)?);
+        let mix = |b: u8, f: u8| (b as f64 + (f as f64 - b as f64) * SHADE).round() as u8;
+        Some(Color::Rgb(mix(br, fr), mix(bg, fg), mix(bb, fb)))
+    }
+
+    /// Names of text colors with too little contrast against the background.
+    /// Colors without a known RGB value, such as indexed colors, are never reported.
+    pub fn low_contrast(&self) -> Vec<&'static str> {
+        [
+            ("fg", self.fg),
+            ("typed_text", self.typed_text),
+            ("incorrect_text", self.incorrect_text),
+        ]
+        .into_iter()
+        .filter(|(_, c)| contrast(*c, self.bg).is_some_and(|r| r < MIN_CONTRAST))
+        .map(|(n, _)| n)
+        .collect()
+    }
+}
+
+/// Approximate RGB value of a color, using the xterm palette for named colors
+fn rgb(c: Color) -> Option<(u8, u8, u8)> {
+    Some(match c {
+        Color::Black => (0, 0, 0),
+        Color::Red => (205, 0, 0),
+        Color::Green => (0, 205, 0),
+        Color::Yellow => (205, 205, 0),
+        Color::Blue => (0, 0, 238),
+        Color::Magenta => (205, 0, 205),
+        Color::Cyan => (0, 205, 205),
+        Color::Gray => (229, 229, 229),
+        Color::DarkGray => (127, 127, 127),
+        Color::LightRed => (255, 0, 0),
+        Color::LightGreen => (0, 255, 0),
+        Color::LightYellow => (255, 255, 0),
+        Color::LightBlue => (92, 92, 255),
+        Color::LightMagenta => (255, 0, 255),
+        Color::LightCyan => (0, 255, 255),
+        Color::White => (255, 255, 255),
+        Color::Rgb(r, g, b) => (r, g, b),
+        _ => return None,
+    })
+}
+
+/// Relative luminance of an sRGB color, from 0 for black to 1 for white
+fn luminance((r, g, b): (u8, u8, u8)) -> f64 {
+    let lin = |c: u8| {
+        let c = c as f64 / 255.0;
+        if c <= 0.03928 {
+            c / 12.92
+        } else {
+            ((c + 0.055) / 1.055).powf(2.4)
+        }
+    };
+    0.2126 * lin(r) + 0.7152 * lin(g) + 0.0722 * lin(b)
+}
+
+/// Contrast ratio between two colors, from 1 for identical to 21 for black on white
+fn contrast(a: Color, b: Color) -> Option<f64> {
+    let (a, b) = (luminance(rgb(a)?), luminance(rgb(b)?));
+    Some((a.max(b) + 0.05) / (a.min(b) + 0.05))
+}
+
 #[derive(Deserialize, Serialize)]
 #[serde(default)]
 /// Specific UI configuration to show or hide elements and change behaviours.
@@ -114,6 +393,49 @@ pub struct UiCfg {
     pub show_clock: bool,
     /// 12 or 24 hour clock
     pub hour_24: bool,
+    /// Only update the clock on input or a change of screen instead of every second, for terminals that flicker on redraw.
+    /// The clock lags while idle
+    pub lazy_clock: bool,
+    /// Set the terminal window title to the current test
+    pub set_terminal_title: bool,
+    /// Hide the modeline and status bar, only showing statuses briefly over the body
+    pub minimal: bool,
+    /// Don't color incorrect characters, instead counting errors in the modeline
+    pub quiet_errors: bool,
+    /// Style correctly typed characters with `typed_text`, otherwise like untyped text so only errors stand out
+    pub highlight_correct: bool,
+    /// Show rotating statistics after this many idle seconds on the test screen, 0 to disable
+    pub idle_stats: u64,
+    /// Show the test this many lines at a time, turning the page after its last word, 0 to scroll
+    pub lines_per_page: usize,
+    /// Widest the test is drawn in columns, such as on ultrawide terminals. 0 for the full width
+    pub max_test_width: u16,
+    /// Where the test sits when narrowed by `max_test_width`, `left` or `center`
+    pub test_alignment: TestAlignment,
+    /// Show only the line being typed, hiding those before and after it. Overrides `lines_per_page`
+    pub focus_line: bool,
+    /// How the caret is drawn, `block`, `bar`, or `underline`. Cycle through them with <F2>
+    pub caret: CaretStyle,
+    /// Warn on startup when theme text colors are hard to read on the background
+    pub check_contrast: bool,
+    /// Show a bar of keystrokes per second over the last few seconds while testing
+    pub show_kps: bool,
+    /// Blank cells between the terminal edges and the UI
+    pub margin: u16,
+    /// Minimum milliseconds a screen is shown before automatically changing, such as to results.
+    /// Screens changed with a key are never delayed
+    pub min_screen_ms: u64,
+    /// Flash a metronome in the modeline at this many beats per minute while testing, 0 to disable.
+    /// Only a pacing aid, it doesn't affect results
+    pub metronome_bpm: u32,
+    /// List the last few mistyped characters in the modeline while testing, as expected→typed
+    pub error_ticker: bool,
+    /// Shade every other group of this many letters in untyped words, to practice reading in chunks. 0 to disable
+    pub chunk_size: usize,
+    /// Show the main keys of the current screen in the status bar while there is no message
+    pub show_hints: bool,
+    /// Slide and fade in the results screen. Any key skips the animation
+    pub animations: bool,
 }
 
 impl Default for UiCfg {
@@ -121,6 +443,305 @@ impl Default for UiCfg {
         Self {
             show_clock: true,
             hour_24: true,
+            lazy_clock: false,
+            set_terminal_title: true,
+            minimal: false,
+            quiet_errors: false,
+            highlight_correct: true,
+            idle_stats: 0,
+            lines_per_page: 0,
+            max_test_width: 0,
+            test_alignment: TestAlignment::default(),
+            focus_line: false,
+            caret: CaretStyle::default(),
+            check_contrast: true,
+            show_kps: false,
+            margin: 0,
+            min_screen_ms: 0,
+            metronome_bpm: 0,
+            error_ticker: false,
+            chunk_size: 0,
+            show_hints: false,
+            animations: false,
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Default)]
+#[serde(default)]
+/// Typing test behaviour.
+pub struct TestCfg {
+    /// Only test the `n` most common words of the language, 0 for all. Assumes a frequency-ordered word list
+    pub top_words: usize,
+    /// Capitalize the first word of each test, like the start of a sentence
+    pub capitalize_first: bool,
+    /// Count a space pressed before any other input, skipping the first word. Ignored otherwise
+    pub count_leading_space: bool,
+    /// Type every word with its letters reversed
+    pub reverse: bool,
+    /// Pause the test while the terminal is unfocused, on terminals that report focus
+    pub pause_on_blur: bool,
+    /// Freeze the test on completion until a key is pressed, instead of going straight to results
+    pub freeze_on_complete: bool,
+    /// Start a new test after the results have been shown this many seconds without a keypress, 0 to disable
+    pub results_dwell_secs: u64,
+    /// End the test early when the percentage of correct keypresses falls below this, 0 to disable
+    pub min_live_accuracy: f64,
+    /// Never test words containing any of these characters, such as `"0123456789"`
+    pub exclude_chars: String,
+    /// Key that skips the current word, leaving it missed, such as `ctrl+s`. Empty to disable
+    pub skip_word_key: String,
+    /// Build tests from the practice queue of problem words instead of the language
+    pub practice: bool,
+    /// Build tests from the words missed most often over all of history, before any practice queue
+    pub nemesis: bool,
+    /// Type an endless stream of words with no defined end, never recorded
+    pub zen: bool,
+    /// Bias random words towards longer ones when positive and shorter ones when negative, from -1.0 to 1.0
+    pub difficulty: f64,
+    /// Capitalize sentence starts and add punctuation to words of languages that aren't punctuated
+    pub inject_punctuation: bool,
+    /// Hide untyped characters behind a placeholder, so words are typed from memory
+    pub obscure: bool,
+    /// Show the words this many seconds before obscuring them, or until typing starts
+    pub obscure_reveal_secs: u64,
+    /// Ignore characters typed past the end of a word instead of counting them as errors
+    pub ignore_overflow: bool,
+    /// Random words from the language typed before each test to get going, dimmed and never scored
+    pub warmup_words: usize,
+    /// Count a space with nothing typed in the word yet, such as a doubled space, as an error instead of skipping the word
+    pub strict_spaces: bool,
+    /// Never backspace out of the current word, so words are committed once a space is typed
+    pub lock_completed_words: bool,
+    /// Allow backspacing into a previous word that was typed correctly, which is otherwise locked
+    pub allow_backspace_correct: bool,
+    /// Treat the Delete key as Backspace instead of ignoring it
+    pub delete_as_backspace: bool,
+    /// Suggest checking Caps Lock the first time in a test a character is typed in the wrong case
+    pub caps_lock_warning: bool,
+}
+
+#[derive(Deserialize, Serialize)]
+#[serde(default)]
+/// Result history and statistics.
+pub struct StatsCfg {
+    /// Unit to display typing speed in, `wpm` or `cpm`
+    pub unit: SpeedUnit,
+    /// Maximum number of results kept in history, oldest are dropped first. 0 keeps nothing
+    pub max_history: usize,
+    /// Don't record the first completed test of each session
+    pub skip_warmup: bool,
+    /// Keypresses less than this many milliseconds after the previous count as burst typing
+    pub burst_ms: u64,
+    /// Tests run back-to-back when calibrating a baseline speed with <F5>
+    pub calibration_tests: usize,
+    /// Save the test in progress this often, so it can be resumed with <F8> after a crash. 0 to disable
+    pub autosave_secs: u64,
+}
+
+impl Default for StatsCfg {
+    fn default() -> Self {
+        Self {
+            unit: SpeedUnit::default(),
+            max_history: 10_000,
+            skip_warmup: false,
+            burst_ms: 100,
+            calibration_tests: 5,
+            autosave_secs: 30,
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone)]
+#[serde(default)]
+/// Keys of actions, such as `ctrl+c`, `alt+n`, `f5`, `space`, or a single character.
+/// Several keys are separated by spaces, such as `esc q`. Empty to unbind.
+pub struct KeybindsCfg {
+    /// Quit without recording the test in progress
+    pub quit: String,
+    /// Record the test in progress and quit
+    pub save_quit: String,
+    /// Discard the test in progress for one with new words
+    pub restart: String,
+    /// Show the about screen
+    pub help: String,
+    /// Cycle through caret styles
+    pub caret: String,
+    /// Edit the config file, reloading it afterwards
+    pub edit_config: String,
+    /// Preview the built-in themes
+    pub themes: String,
+    /// Calibrate a goal speed over several tests
+    pub calibrate: String,
+    /// Switch to the next favorite language
+    pub next_favorite: String,
+    /// Show statistics from history
+    pub statistics: String,
+    /// Resume a test interrupted in an earlier session
+    pub resume: String,
+    /// Discard a test interrupted in an earlier session, so tests of this one are autosaved again
+    pub discard_recovery: String,
+    /// Re-read the theme from the config file, keeping the test in progress
+    pub reload_theme: String,
+    /// Start a new test of each word count preset, 10, 25, 50, and 100 words
+    pub presets: [String; 4],
+    /// Go to the next screen, other than from the test where it ends the test instead
+    pub next_screen: String,
+    /// Go to the previous screen
+    pub previous_screen: String,
+    /// Leave a screen for the one before it
+    pub back: String,
+    /// Move on to the next word while testing
+    pub next_word: String,
+    /// Delete the last character while testing
+    pub backspace: String,
+    /// Delete the current word while testing
+    pub delete_word: String,
+    /// End the test, going to its results
+    pub end_test: String,
+    /// Show characters deleted while typing in the results word review
+    pub review_deleted: String,
+    /// Show speed under other common definitions on the results screen
+    pub wpm_definitions: String,
+    /// Export every keypress of the last test from the results screen
+    pub export_keystrokes: String,
+    /// Export the statistics shown as a markdown report
+    pub export_report: String,
+    /// Browse to the previous statistics label or built-in theme
+    pub browse_previous: String,
+    /// Browse to the next statistics label or built-in theme
+    pub browse_next: String,
+    /// Apply the previewed theme, or set the calibrated goal
+    pub confirm: String,
+}
+
+impl Default for KeybindsCfg {
+    fn default() -> Self {
+        Self {
+            quit: "Ctrl+C".to_string(),
+            save_quit: "Ctrl+Q".to_string(),
+            restart: "Ctrl+R".to_string(),
+            help: "F1".to_string(),
+            caret: "F2".to_string(),
+            edit_config: "F3".to_string(),
+            themes: "F4".to_string(),
+            calibrate: "F5".to_string(),
+            next_favorite: "F6".to_string(),
+            statistics: "F7".to_string(),
+            resume: "F8".to_string(),
+            discard_recovery: "Ctrl+F8".to_string(),
+            reload_theme: "Ctrl+L".to_string(),
+            presets: ["Alt+1", "Alt+2", "Alt+3", "Alt+4"].map(String::from),
+            next_screen: "Tab".to_string(),
+            previous_screen: "Shift+Tab".to_string(),
+            back: "Esc q".to_string(),
+            next_word: "Space".to_string(),
+            backspace: "Backspace".to_string(),
+            delete_word: "Ctrl+Backspace Alt+Backspace".to_string(),
+            end_test: "Tab".to_string(),
+            review_deleted: "d".to_string(),
+            wpm_definitions: "w".to_string(),
+            export_keystrokes: "k".to_string(),
+            export_report: "e".to_string(),
+            browse_previous: "Left".to_string(),
+            browse_next: "Right".to_string(),
+            confirm: "Enter".to_string(),
+        }
+    }
+}
+
+impl KeybindsCfg {
+    /// Every action's keys by name
+    fn actions_mut(&mut self) -> [(&'static str, &mut String); 31] {
+        let [p1, p2, p3, p4] = &mut self.presets;
+        [
+            ("quit", &mut self.quit),
+            ("save_quit", &mut self.save_quit),
+            ("restart", &mut self.restart),
+            ("help", &mut self.help),
+            ("caret", &mut self.caret),
+            ("edit_config", &mut self.edit_config),
+            ("themes", &mut self.themes),
+            ("calibrate", &mut self.calibrate),
+            ("next_favorite", &mut self.next_favorite),
+            ("statistics", &mut self.statistics),
+            ("resume", &mut self.resume),
+            ("discard_recovery", &mut self.discard_recovery),
+            ("reload_theme", &mut self.reload_theme),
+            ("presets", p1),
+            ("presets", p2),
+            ("presets", p3),
+            ("presets", p4),
+            ("next_screen", &mut self.next_screen),
+            ("previous_screen", &mut self.previous_screen),
+            ("back", &mut self.back),
+            ("next_word", &mut self.next_word),
+            ("backspace", &mut self.backspace),
+            ("delete_word", &mut self.delete_word),
+            ("end_test", &mut self.end_test),
+            ("review_deleted", &mut self.review_deleted),
+            ("wpm_definitions", &mut self.wpm_definitions),
+            ("export_keystrokes", &mut self.export_keystrokes),
+            ("export_report", &mut self.export_report),
+            ("browse_previous", &mut self.browse_previous),
+            ("browse_next", &mut self.browse_next),
+            ("confirm", &mut self.confirm),
+        ]
+    }
+
+    /// Unbind actions bound to keys that can't be parsed, or to keys that would type text while
+    /// testing, returning their names
+    pub fn unbind_invalid(&mut self) -> Vec<&'static str> {
+        let mut names = Vec::new();
+        for (name, keys) in self.actions_mut() {
+            // only used on screens without typing, or to type
+            let untyped = matches!(
+                name,
+                "back"
+                    | "next_word"
+                    | "review_deleted"
+                    | "wpm_definitions"
+                    | "export_keystrokes"
+                    | "export_report"
+            );
+            let valid = keys.split_whitespace().all(|k| {
+                parse_key(k).is_some_and(|(code, mods)| untyped || !types_text(code, mods))
+            });
+            if !valid {
+                keys.clear();
+                if !names.contains(&name) {
+                    names.push(name);
+                }
+            }
+        }
+        names
+    }
+}
+
+#[derive(Deserialize, Serialize)]
+#[serde(default)]
+/// Results screen layout.
+pub struct ResultsCfg {
+    /// Sections shown on the results screen from top to bottom, any of
+    /// `speed`, `burst`, `errors`, `hands`, `definitions`, `timeline`, `histogram`, and `review`
+    pub widgets: Vec<String>,
+}
+
+impl Default for ResultsCfg {
+    fn default() -> Self {
+        Self {
+            widgets: [
+                "speed",
+                "burst",
+                "errors",
+                "hands",
+                "definitions",
+                "timeline",
+                "histogram",
+                "review",
+            ]
+            .map(String::from)
+            .to_vec(),
         }
     }
 }
@@ -132,18 +753,45 @@ struct Args {
     /// List available languages
     #[arg(long)]
     list: bool,
+    /// Check a language file, by name or path, for problems
+    #[arg(long, value_name = "LANG")]
+    lint: Option<String>,
     /// Select language
     #[arg(short, long)]
     lang: Option<String>,
     /// Specify test word count
     #[arg(short, long)]
     words: Option<u32>,
+    /// Label results of this session
+    #[arg(long)]
+    label: Option<String>,
+    /// Only test the N most common words of the language
+    #[arg(long, value_name = "N")]
+    top: Option<usize>,
+    /// Replay the test with this seed, as shown on the results screen
+    #[arg(long, value_name = "SEED")]
+    seed: Option<u64>,
+    /// Test words from the practice queue of previously missed words
+    #[arg(long)]
+    practice: bool,
+    /// Test the words missed most often over all of history
+    #[arg(long)]
+    nemesis: bool,
+    /// Type an endless stream of words, until quitting
+    #[arg(long)]
+    zen: bool,
+    /// Add words to the practice queue
+    #[arg(long, value_name = "WORD", num_args = 1..)]
+    practice_add: Vec<String>,
     /// Preview colors
     #[arg(long)]
     help_colors: bool,
     /// Print help about the config file
     #[arg(long)]
     help_config: bool,
+    /// Headlessly generate and type N tests, printing timings
+    #[arg(long, value_name = "N", hide = true)]
+    bench: Option<usize>,
 }
 
 impl Args {
@@ -153,10 +801,45 @@ impl Args {
 
         if a.list {
             let mut h = io::stdout().lock();
-            let _ = writeln!(h, "Available languages:");
-            for l in Lang::list()? {
+            let (langs, shadowed) = Lang::list(&Config::file_lang_dirs())?;
+            if langs.is_empty() {
+                let _ = writeln!(h, "no languages installed");
+            } else {
+                let _ = writeln!(h, "Available languages:");
+            }
+            for l in langs {
                 let _ = writeln!(h, "  {}", l.file_name().unwrap().to_str().unwrap());
             }
+            for l in shadowed {
+                let _ = writeln!(
+                    h,
+                    "Warning: {} is shadowed by a language of the same name in an earlier directory",
+                    l.display()
+                );
+            }
+        } else if let Some(l) = &a.lint {
+            let p = PathBuf::from(l);
+            let p = if p.is_file() {
+                p
+            } else {
+                Lang::find(l, &Config::file_lang_dirs())
+                    .into_iter()
+                    .next()
+                    .unwrap_or_else(|| Lang::path().join(l))
+            };
+            let issues = Lang::lint(&p)?;
+
+            let mut h = io::stdout().lock();
+            let _ = writeln!(h, "Linting {}:", p.display());
+            for i in issues.iter() {
+                let _ = writeln!(h, "  {i}");
+            }
+            if issues.is_empty() {
+                let _ = writeln!(h, "No problems found.");
+            } else {
+                let _ = writeln!(h, "{} problem(s) found.", issues.len());
+                process::exit(0b1);
+            }
         } else if a.help_config {
             println!(
                 r#"arstyper Configuration Information
@@ -170,10 +853,29 @@ The config file is located at one of the following locations:
   Linux: ~/.config/arstyper.toml
   MacOS: $HOME/Library/Application Support/arstyper.toml
   Windows: %localappdata%/arstyper.toml
+If arstyper.json exists in the same directory, it is read as JSON instead.
 
 To preview supported Theme Colors:
   arstyper --help-colors"#
             );
+        } else if !a.practice_add.is_empty() {
+            let mut p = Practice::load()?;
+            for w in a.practice_add.iter() {
+                p.add(w);
+            }
+            p.save()?;
+            println!(
+                "Added {} word(s), {} in the practice queue.",
+                a.practice_add.len(),
+                p.len()
+            );
+        } else if let Some(n) = a.bench {
+            bench::run(
+                a.lang.as_deref().unwrap_or(&Config::default().lang),
+                &Config::file_lang_dirs(),
+                n,
+                a.words.unwrap_or(Config::default().word_count) as usize,
+            )?;
         } else if a.help_colors {
             let c = ColorPreview::new();
             c.run()?;
@@ -184,3 +886,101 @@ To preview supported Theme Colors:
         process::exit(0b0);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_json() {
+        let p = Path::new("arstyper.json");
+        let cfg = Config::parse(p, r#"{"word_count": 25, "ui": {"minimal": true}}"#).unwrap();
+        assert_eq!(cfg.word_count, 25);
+        assert!(cfg.ui.minimal);
+        assert_eq!(cfg.lang, "english");
+
+        let e = Config::parse(p, "{\n  \"word_count\": 25,\n}")
+            .err()
+            .unwrap();
+        assert!(e.contains("line 3 column 1"), "{e}");
+
+        // everything else is still toml
+        let cfg = Config::parse(Path::new("arstyper.toml"), "word_count = 10").unwrap();
+        assert_eq!(cfg.word_count, 10);
+    }
+
+    #[test]
+    fn test_overrides() {
+        let p = Path::new("arstyper.toml");
+        let mut cfg = Config::parse(p, "word_count = 10\nlang = \"german\"").unwrap();
+        cfg.overrides = Overrides {
+            word_count: Some(25),
+            zen: true,
+            ..Default::default()
+        };
+        cfg.apply_overrides();
+        assert_eq!(cfg.word_count, 25);
+        assert!(cfg.test.zen);
+        // options not given on the CLI come from the file
+        assert_eq!(cfg.lang, "german");
+    }
+
+    #[test]
+    fn test_keybinds() {
+        assert!(KeybindsCfg::default().unbind_invalid().is_empty());
+        let mut kb = KeybindsCfg {
+            help: "".to_string(),
+            restart: "ctrl+nope".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(kb.unbind_invalid(), vec!["restart"]);
+        assert!(kb.restart.is_empty());
+
+        // unmodified characters would be typed, other than by actions used while not typing
+        let mut kb = KeybindsCfg {
+            quit: "Q".to_string(),
+            caret: "f2 x".to_string(),
+            back: "q".to_string(),
+            next_word: "Enter ;".to_string(),
+            review_deleted: "D".to_string(),
+            confirm: "y".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(kb.unbind_invalid(), vec!["quit", "caret", "confirm"]);
+        assert_eq!(kb.back, "q");
+    }
+
+    #[test]
+    fn test_theme_colors() {
+        let p = Path::new("arstyper.toml");
+        let cfg = Config::parse(
+            p,
+            "[theme]\nfg = \"#1e1e2e\"\nbg = \"LightBlue\"\naccent = \"#nope\"\n",
+        )
+        .unwrap();
+        assert_eq!(cfg.theme.fg, Color::Rgb(30, 30, 46));
+        assert_eq!(cfg.theme.bg, Color::LightBlue);
+        assert_eq!(cfg.theme.accent, ThemeCfg::default().accent);
+        assert_eq!(cfg.theme.invalid, vec!["accent = `#nope`"]);
+
+        // hex colors are written back out as hex
+        let s = toml::to_string(&cfg).unwrap();
+        assert!(s.contains("fg = \"#1E1E2E\""), "{s}");
+    }
+
+    #[test]
+    fn test_low_contrast() {
+        for (name, t) in ThemeCfg::builtin() {
+            assert!(t.low_contrast().is_empty(), "{name}");
+        }
+
+        let t = ThemeCfg {
+            fg: Color::Black,
+            typed_text: Color::Rgb(20, 20, 20),
+            incorrect_text: Color::Indexed(1),
+            ..Default::default()
+        };
+        assert_eq!(t.low_contrast(), vec!["fg", "typed_text"]);
+        assert_eq!(contrast(Color::Black, Color::White).unwrap().round(), 21.0);
+    }
+}
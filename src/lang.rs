@@ -1,25 +1,165 @@
 //! Loading and parsing of language files
+use crate::results;
+use rand::{RngExt, SeedableRng, distr::weighted::WeightedIndex, rngs::StdRng, seq::SliceRandom};
 use std::{
-    fs::{self, File},
-    io::{self, BufRead, Error, ErrorKind},
-    path::PathBuf,
+    cmp::min,
+    collections::{BTreeMap, HashMap, HashSet},
+    fmt, fs,
+    io::Error,
+    path::{Path, PathBuf},
 };
 
+/// Exponent on word length at full difficulty, so a word twice as long is 8 times as likely
+const DIFFICULTY_POWER: f64 = 3.0;
+
+/// A problem found while linting a language file.
+pub struct Issue {
+    /// 1-indexed line number, if the problem is with a specific line
+    pub line: Option<usize>,
+    pub msg: String,
+}
+
+impl Issue {
+    fn at(line: usize, msg: &str) -> Self {
+        Self {
+            line: Some(line),
+            msg: msg.to_string(),
+        }
+    }
+}
+
+/// Reasons a language can't be loaded.
+#[derive(Debug)]
+pub enum LangError {
+    /// No language directory has a language of this name
+    NotFound(String),
+    /// The language file couldn't be read
+    Read(PathBuf, Error),
+    /// The header has both `select_one` and `select_all`
+    ExclusiveFlags(PathBuf),
+    /// The word list has no words to test
+    Empty(PathBuf),
+    /// The saved positions of `inorder` languages couldn't be read
+    Inorder(Error),
+}
+
+impl fmt::Display for LangError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::NotFound(s) => write!(
+                f,
+                "Language `{s}` isn't installed, see `arstyper --list` for the languages that are."
+            ),
+            Self::Read(p, e) => write!(f, "Error reading {}: {e}", p.display()),
+            Self::ExclusiveFlags(p) => write!(
+                f,
+                "Error reading {}: Language header has mutually exclusive options `select_one` and `select_all`! Please remove at least one of those options to use this language.",
+                p.display()
+            ),
+            Self::Empty(p) => write!(f, "Error reading {}: Language has no words!", p.display()),
+            Self::Inorder(e) => write!(f, "Error reading saved language positions: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for LangError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Read(_, e) | Self::Inorder(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<LangError> for Error {
+    fn from(e: LangError) -> Self {
+        Error::other(e)
+    }
+}
+
+impl fmt::Display for Issue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.line {
+            Some(n) => write!(f, "line {n}: {}", self.msg),
+            None => write!(f, "{}", self.msg),
+        }
+    }
+}
+
+/// Trim a line and collapse runs of whitespace into single spaces.
+fn normalize_spaces(l: &str) -> String {
+    l.split_whitespace().collect::<Vec<&str>>().join(" ")
+}
+
+/// Path to the saved `inorder` index of each language
+fn inorder_path() -> PathBuf {
+    results::dir().join("inorder.json")
+}
+
+fn load_inorder_indices(p: &Path) -> Result<BTreeMap<String, usize>, std::io::Error> {
+    Ok(results::load_json(p)?.unwrap_or_default())
+}
+
 /// Representation of a language file.
 pub struct Lang {
     pub name: String,
     pub inorder: bool,
-    _inorder_index: usize,
+    /// Where the next `inorder` test starts in the word list
+    inorder_index: usize,
     pub punctuated: bool,
     pub select_one: bool,
     pub select_all: bool,
     pub words: Vec<String>,
+    /// Lines of the file skipped for not being valid UTF-8
+    pub invalid_lines: usize,
+    /// Only generate from the first `top` words, 0 for all
+    top: usize,
+    /// Never generate words containing these characters
+    exclude: Vec<char>,
+    /// Bias towards longer words when positive, shorter when negative, from -1 to 1
+    difficulty: f64,
 }
 
 impl Lang {
-    /// Open a language files by its name, assuming it exists.
-    pub fn get_by_name(s: &str) -> Result<Self, std::io::Error> {
-        Self::get_by_path(&Self::path().join(s), s)
+    /// Open a language file by its name from the first of `dirs` that has it.
+    pub fn get_by_name(s: &str, dirs: &[PathBuf]) -> Result<Self, LangError> {
+        let p = Self::find(s, dirs)
+            .into_iter()
+            .next()
+            .ok_or_else(|| LangError::NotFound(s.to_string()))?;
+        let mut l = Self::get_by_path(&p, s)?;
+        if l.inorder {
+            l.inorder_index = load_inorder_indices(&inorder_path())
+                .map_err(LangError::Inorder)?
+                .get(s)
+                .copied()
+                .unwrap_or(0);
+        }
+        Ok(l)
+    }
+
+    /// Move an `inorder` language on past `n` typed words, wrapping around, so the next test
+    /// continues after them.
+    pub fn advance_inorder(&mut self, n: usize) {
+        let len = self.inorder_words().len();
+        self.inorder_index = (self.inorder_index + n) % len.max(1);
+    }
+
+    /// Words of an `inorder` language in the order they are tested.
+    /// Counted in words rather than lines, so a test can stop and the next continue mid-line
+    fn inorder_words(&self) -> Vec<String> {
+        self.pool()
+            .into_iter()
+            .flat_map(|l| l.split_whitespace().map(String::from))
+            .collect()
+    }
+
+    /// Save where the next `inorder` test starts, so it continues there next session.
+    pub fn save_inorder_index(&self) -> Result<(), std::io::Error> {
+        let p = inorder_path();
+        let mut indices = load_inorder_indices(&p)?;
+        indices.insert(self.name.clone(), self.inorder_index);
+        results::save_json(&p, &indices)
     }
 
     /// Open a language file by actual path, assuming it exists.
@@ -40,90 +180,562 @@ impl Lang {
     /// word2
     /// ...
     /// ```
-    pub fn get_by_path(p: &PathBuf, name: &str) -> Result<Self, std::io::Error> {
-        let f = File::open(&p)?;
+    pub fn get_by_path(p: &PathBuf, name: &str) -> Result<Self, LangError> {
+        let (s, _) = Self::parse(p, name).map_err(|e| LangError::Read(p.clone(), e))?;
 
-        let buf = io::BufReader::new(f).lines().map_while(Result::ok);
-        let mut s = Self {
+        // sanity check
+        if s.select_one && s.select_all {
+            return Err(LangError::ExclusiveFlags(p.clone()));
+        }
+        if s.words.is_empty() {
+            return Err(LangError::Empty(p.clone()));
+        }
+
+        Ok(s)
+    }
+
+    /// Language of the given words without any flags, not backed by a file.
+    pub fn from_words(name: &str, words: Vec<String>) -> Self {
+        Self {
             name: name.to_string(),
             inorder: false,
-            _inorder_index: 0,
+            inorder_index: 0,
             punctuated: false,
             select_one: false,
             select_all: false,
-            words: Vec::with_capacity(250),
-        };
+            words,
+            invalid_lines: 0,
+            top: 0,
+            exclude: Vec::new(),
+            difficulty: 0.0,
+        }
+    }
+
+    /// Check a language file for problems, without stopping at the first one.
+    pub fn lint(p: &PathBuf) -> Result<Vec<Issue>, std::io::Error> {
+        Ok(Self::parse(p, "")?.1)
+    }
+
+    /// Parse a language file, collecting any problems found along the way.
+    /// Lines that aren't valid UTF-8 are skipped, counted in `invalid_lines`.
+    fn parse(p: &PathBuf, name: &str) -> Result<(Self, Vec<Issue>), std::io::Error> {
+        let bytes = fs::read(p)?;
+        let mut issues = Vec::new();
+
+        let mut s = Self::from_words(name, Vec::with_capacity(250));
+
+        // first line each word was seen on, for finding duplicates
+        let mut seen: HashMap<String, usize> = HashMap::new();
 
         // separate lang file by header and word list with a keyword
-        // slightly less efficient than splitting a buf but non-issue
         let mut header = true;
-        for l in buf {
+        let mut lines = bytes.split(|b| *b == b'\n').collect::<Vec<&[u8]>>();
+        if lines.last().is_some_and(|l| l.is_empty()) {
+            lines.pop();
+        }
+        for (i, l) in lines.into_iter().enumerate() {
+            let n = i + 1;
+            let l = l.strip_suffix(b"\r").unwrap_or(l);
+            let Ok(l) = str::from_utf8(l) else {
+                issues.push(Issue::at(n, "line is not valid UTF-8"));
+                s.invalid_lines += 1;
+                continue;
+            };
+
             if header {
                 if l == "-----BEGIN WORDLIST-----" {
                     header = false;
                 } else {
                     // flags
-                    match l.as_str() {
+                    match l {
                         "inorder" => s.inorder = true,
                         "punctuated" => s.punctuated = true,
                         "select_one" => s.select_one = true,
                         "select_all" => s.select_all = true,
-                        _ => (),
+                        _ => issues.push(Issue::at(n, &format!("unknown flag `{l}`"))),
+                    }
+                    if matches!(l, "select_one" | "select_all") && s.select_one && s.select_all {
+                        issues.push(Issue::at(
+                            n,
+                            "mutually exclusive flags `select_one` and `select_all`",
+                        ));
                     }
                 }
             } else {
-                s.words.push(l);
+                if l.trim() != l {
+                    issues.push(Issue::at(n, "leading or trailing whitespace"));
+                }
+                match seen.get(l) {
+                    Some(first) => issues.push(Issue::at(n, &format!("duplicate of line {first}"))),
+                    None => {
+                        seen.insert(l.to_string(), n);
+                    }
+                }
+                // quotes and sentences may come with invisible spacing that can't fairly be typed
+                if s.punctuated || s.select_one {
+                    s.words.push(normalize_spaces(l));
+                } else {
+                    s.words.push(l.to_string());
+                }
             }
         }
-        // sanity check
-        if s.select_one && s.select_all {
-            return Err(Error::new(
-                ErrorKind::Other,
-                format!(
-                    "Error reading {}: Language header has mutually exclusive options `select_one` and `select_all`! Please remove at least one of those options to use this language.",
-                    p.display()
-                ),
-            ));
+
+        if header {
+            issues.push(Issue {
+                line: None,
+                msg: "missing `-----BEGIN WORDLIST-----`".to_string(),
+            });
+        }
+        if s.words.is_empty() {
+            issues.push(Issue {
+                line: None,
+                msg: "word list is empty".to_string(),
+            });
         }
 
-        // unimplemented warn
-        {
-            // TODO implement these lol
-            for (b, s) in vec![
-                (s.inorder, "inorder"),
-                (s.punctuated, "punctuated"),
-                (s.select_one, "select_one"),
-                (s.select_all, "select_all"),
-            ] {
-                if b {
-                    println!(
-                        "The flag `{s}` is not yet implemented! Your language file may not behave as expected."
-                    );
+        Ok((s, issues))
+    }
+
+    /// Language files in every one of `dirs`, and those shadowed by a language of the same name in an earlier dir.
+    /// Dirs that don't exist yet and entries that can't be read are skipped.
+    pub fn list(dirs: &[PathBuf]) -> Result<(Vec<PathBuf>, Vec<PathBuf>), std::io::Error> {
+        let (mut langs, mut shadowed) = (Vec::new(), Vec::new());
+        let mut names = HashSet::new();
+        for d in dirs {
+            let entries = match fs::read_dir(d) {
+                Ok(e) => e,
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+                Err(e) => return Err(e),
+            };
+            let mut files = entries
+                .filter_map(|e| e.ok().map(|e| e.path()))
+                .filter(|p| p.is_file())
+                .collect::<Vec<PathBuf>>();
+            files.sort();
+            for p in files {
+                if names.insert(p.file_name().unwrap().to_owned()) {
+                    langs.push(p);
+                } else {
+                    shadowed.push(p);
                 }
             }
         }
-
-        return Ok(s);
+        Ok((langs, shadowed))
     }
 
-    /// Return list of all language paths.
-    pub fn list() -> Result<Vec<PathBuf>, std::io::Error> {
-        Ok(fs::read_dir(Self::path())?
-            .map(|e| e.unwrap().path())
+    /// Paths of language `s` in each of `dirs` that has it, in order. The first is the one used.
+    pub fn find(s: &str, dirs: &[PathBuf]) -> Vec<PathBuf> {
+        dirs.iter()
+            .map(|d| d.join(s))
             .filter(|p| p.is_file())
-            .collect::<Vec<PathBuf>>())
+            .collect()
     }
 
-    /// Path to language dir.
-    fn path() -> PathBuf {
+    /// Path to the default language dir, searched before any configured in `lang_paths`.
+    pub fn path() -> PathBuf {
         dirs::data_local_dir().unwrap().join("arstyper")
     }
 
-    /// Get n word iterator of this language for tests
-    pub fn gen_words(&self, n: usize) -> impl Iterator<Item = String> {
-        std::iter::from_fn(|| -> Option<String> {
-            Some(self.words[rand::random_range(0..self.words.len())].clone())
+    /// Restrict generation to the first `n` words, assuming the word list is ordered by frequency.
+    /// `n` is clamped to the word list length, returning false if it had to be. 0 disables.
+    pub fn set_top(&mut self, n: usize) -> bool {
+        self.top = min(n, self.words.len());
+        n <= self.words.len()
+    }
+
+    /// Don't generate words containing any of `chars`.
+    /// If no words would be left, nothing is excluded and false is returned.
+    pub fn set_exclude(&mut self, chars: &str) -> bool {
+        self.exclude = chars.chars().collect();
+        if !self.exclude.is_empty() && self.pool().is_empty() {
+            self.exclude.clear();
+            return false;
+        }
+        true
+    }
+
+    /// Bias random words by length, towards longer ones when positive and shorter when negative.
+    /// Clamped to -1..=1, 0 picks every word equally
+    pub fn set_difficulty(&mut self, d: f64) {
+        self.difficulty = d.clamp(-1.0, 1.0);
+    }
+
+    /// Words available for generation
+    fn pool(&self) -> Vec<&String> {
+        let words = match self.top {
+            0 => &self.words[..],
+            n => &self.words[..n],
+        };
+        words
+            .iter()
+            .filter(|w| !w.chars().any(|c| self.exclude.contains(&c)))
+            .collect()
+    }
+
+    /// Get n word iterator of this language for tests.
+    /// The same seed always generates the same words for the same language and top words.
+    ///
+    /// `inorder` languages instead continue after the words typed so far, wrapping around.
+    /// They only move on with [`Lang::advance_inorder`], so untyped words are generated again.
+    /// `select_one` languages test every word of a single random line, ignoring `n`.
+    /// `select_all` languages test every word once in a shuffled order, ignoring `n`.
+    pub fn gen_words(&self, n: usize, seed: u64) -> Box<dyn Iterator<Item = String> + '_> {
+        if self.select_one {
+            let pool = self.pool();
+            if pool.is_empty() {
+                return Box::new(std::iter::empty());
+            }
+            let line = pool[StdRng::seed_from_u64(seed).random_range(0..pool.len())];
+            return Box::new(line.split_whitespace().map(String::from));
+        }
+
+        if self.select_all {
+            // lines are shuffled whole, so sentences stay in order
+            let mut lines = self.pool();
+            lines.shuffle(&mut StdRng::seed_from_u64(seed));
+            return Box::new(
+                lines
+                    .into_iter()
+                    .flat_map(|l| l.split_whitespace().map(String::from)),
+            );
+        }
+
+        if self.inorder {
+            let words = self.inorder_words();
+            let start = self.inorder_index % words.len().max(1);
+            return Box::new(words.into_iter().cycle().skip(start).take(n));
+        }
+
+        Box::new(self.sample(n, seed))
+    }
+
+    /// `n` random words of this language regardless of its flags, splitting lines of several words.
+    /// Words are weighted by length to the power of `difficulty * DIFFICULTY_POWER`,
+    /// and the same word never comes twice in a row unless it is the only one
+    pub fn sample(&self, n: usize, seed: u64) -> impl Iterator<Item = String> + '_ {
+        let pool = self.pool();
+        let mut rng = StdRng::seed_from_u64(seed);
+        // uniform at 0, so seeds keep generating the same words
+        let weights = (self.difficulty != 0.0)
+            .then(|| {
+                WeightedIndex::new(pool.iter().map(|w| {
+                    (w.chars().count().max(1) as f64).powf(self.difficulty * DIFFICULTY_POWER)
+                }))
+                .ok()
+            })
+            .flatten();
+        // a re-roll can only find another word if there is one, so a list of one word can't re-roll forever
+        let varied = pool
+            .iter()
+            .map(|l| l.split_whitespace().next())
+            .collect::<HashSet<Option<&str>>>()
+            .len()
+            > 1;
+        let mut last = None;
+        std::iter::from_fn(move || -> Option<&String> {
+            if pool.is_empty() {
+                return None;
+            }
+            let mut pick = || match &weights {
+                Some(d) => pool[rng.sample(d)],
+                None => pool[rng.random_range(0..pool.len())],
+            };
+            // compared by the words either side of the boundary, as lines may hold several
+            let mut line = pick();
+            while varied && line.split_whitespace().next() == last {
+                line = pick();
+            }
+            last = line.split_whitespace().last();
+            Some(line)
         })
+        .flat_map(|l| l.split_whitespace().map(String::from))
         .take(n)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lang_dirs() {
+        let root = std::env::temp_dir().join(format!("arstyper-dirs-{}", std::process::id()));
+        let dirs = [root.join("a"), root.join("b")];
+        for (d, names) in dirs
+            .iter()
+            .zip([&["english", "quotes"][..], &["english", "code"]])
+        {
+            fs::create_dir_all(d).unwrap();
+            for n in names {
+                fs::write(
+                    d.join(n),
+                    format!("-----BEGIN WORDLIST-----\n{}\n", d.display()),
+                )
+                .unwrap();
+            }
+        }
+
+        let (langs, shadowed) = Lang::list(&dirs).unwrap();
+        let missing = Lang::list(&[root.join("missing")]).unwrap();
+        let found = Lang::find("english", &dirs);
+        let l = Lang::get_by_name("code", &dirs).unwrap();
+        let _ = fs::remove_dir_all(&root);
+        assert_eq!(
+            langs,
+            vec![
+                dirs[0].join("english"),
+                dirs[0].join("quotes"),
+                dirs[1].join("code")
+            ]
+        );
+        assert_eq!(shadowed, vec![dirs[1].join("english")]);
+        assert_eq!(missing, (Vec::new(), Vec::new()));
+        assert_eq!(
+            found,
+            vec![dirs[0].join("english"), dirs[1].join("english")]
+        );
+        assert_eq!(l.words, vec![dirs[1].display().to_string()]);
+    }
+
+    #[test]
+    fn test_lang_errors() {
+        let root = std::env::temp_dir().join(format!("arstyper-errors-{}", std::process::id()));
+        fs::create_dir_all(&root).unwrap();
+        fs::write(
+            root.join("both"),
+            "select_one\nselect_all\n-----BEGIN WORDLIST-----\nword\n",
+        )
+        .unwrap();
+        fs::write(root.join("empty"), "-----BEGIN WORDLIST-----\n").unwrap();
+
+        let dirs = [root.clone()];
+        let missing = Lang::get_by_name("missing", &dirs);
+        let both = Lang::get_by_name("both", &dirs);
+        let empty = Lang::get_by_name("empty", &dirs);
+        let _ = fs::remove_dir_all(&root);
+        assert!(matches!(missing, Err(LangError::NotFound(s)) if s == "missing"));
+        assert!(matches!(both, Err(LangError::ExclusiveFlags(p)) if p == root.join("both")));
+        assert!(matches!(empty, Err(LangError::Empty(_))));
+    }
+
+    #[test]
+    fn test_lint() {
+        let p = std::env::temp_dir().join(format!("arstyper-lint-{}", std::process::id()));
+        fs::write(
+            &p,
+            b"select_one\nbogus\nselect_all\n-----BEGIN WORDLIST-----\nword\n word\nword\n\xff\xfe\n",
+        )
+        .unwrap();
+        let issues = Lang::lint(&p)
+            .unwrap()
+            .iter()
+            .map(|i| i.to_string())
+            .collect::<Vec<String>>();
+        let _ = fs::remove_file(&p);
+
+        assert_eq!(
+            issues,
+            vec![
+                "line 2: unknown flag `bogus`",
+                "line 3: mutually exclusive flags `select_one` and `select_all`",
+                "line 6: leading or trailing whitespace",
+                "line 7: duplicate of line 5",
+                "line 8: line is not valid UTF-8",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_invalid_utf8() {
+        let p = std::env::temp_dir().join(format!("arstyper-utf8-{}", std::process::id()));
+        fs::write(&p, b"-----BEGIN WORDLIST-----\none\n\xff\xfe\ntwo\n").unwrap();
+        let l = Lang::get_by_path(&p, "test");
+        let _ = fs::remove_file(&p);
+        let l = l.unwrap();
+        // the rest of the file is still read
+        assert_eq!(l.words, vec!["one", "two"]);
+        assert_eq!(l.invalid_lines, 1);
+    }
+
+    #[test]
+    fn test_punctuated_spacing() {
+        let p = std::env::temp_dir().join(format!("arstyper-quotes-{}", std::process::id()));
+        fs::write(
+            &p,
+            "punctuated\n-----BEGIN WORDLIST-----\n  To be,  or not\tto be. \nWait -- what?!\n",
+        )
+        .unwrap();
+        let (l, _) = Lang::parse(&p, "quotes").unwrap();
+        let _ = fs::remove_file(&p);
+
+        assert_eq!(l.words, vec!["To be, or not to be.", "Wait -- what?!"]);
+    }
+
+    #[test]
+    fn test_exclude_chars() {
+        let mut l = Lang::from_words(
+            "test",
+            ["one", "2nd", "three", "4th"].map(String::from).to_vec(),
+        );
+        assert!(l.set_exclude("0123456789"));
+        assert!(
+            l.gen_words(20, 0)
+                .all(|w| !w.chars().any(|c| c.is_ascii_digit()))
+        );
+
+        // would exclude everything, so nothing is
+        assert!(!l.set_exclude("aeiou0123456789"));
+        assert_eq!(l.pool().len(), 4);
+    }
+
+    #[test]
+    fn test_no_repeats() {
+        let mut l = Lang::from_words("test", ["a", "b"].map(String::from).to_vec());
+        let words = l.gen_words(200, 0).collect::<Vec<String>>();
+        assert_eq!(words.len(), 200);
+        assert!(words.windows(2).all(|w| w[0] != w[1]));
+        l.set_difficulty(1.0);
+        assert!(
+            l.sample(200, 0)
+                .collect::<Vec<String>>()
+                .windows(2)
+                .all(|w| w[0] != w[1])
+        );
+
+        // the same word listed twice, or ending one line and starting the next
+        for words in [vec!["the", "the", "a"], vec!["x the", "the y", "z"]] {
+            let l = Lang::from_words("test", words.into_iter().map(String::from).collect());
+            for seed in 0..10 {
+                let words = l.sample(100, seed).collect::<Vec<String>>();
+                assert!(words.windows(2).all(|w| w[0] != w[1]));
+            }
+        }
+
+        // a single word has to repeat
+        let l = Lang::from_words("test", vec!["a".to_string(), "a".to_string()]);
+        assert_eq!(l.gen_words(3, 0).collect::<Vec<String>>(), vec!["a"; 3]);
+        let l = Lang::from_words("test", vec!["a".to_string()]);
+        assert_eq!(l.gen_words(3, 0).collect::<Vec<String>>(), vec!["a"; 3]);
+    }
+
+    #[test]
+    fn test_difficulty() {
+        let mut l = Lang::from_words(
+            "test",
+            [
+                "a", "to", "the", "word", "quick", "jumped", "example", "keyboard",
+            ]
+            .map(String::from)
+            .to_vec(),
+        );
+        let mean_len = |l: &mut Lang| {
+            let words = l.gen_words(2000, 0).collect::<Vec<String>>();
+            words.iter().map(|w| w.len()).sum::<usize>() as f64 / words.len() as f64
+        };
+        let uniform = l.sample(50, 1).collect::<Vec<String>>();
+        let plain = mean_len(&mut l);
+        l.set_difficulty(1.0);
+        let hard = mean_len(&mut l);
+        l.set_difficulty(-5.0);
+        let easy = mean_len(&mut l);
+        assert!(
+            easy < plain - 1.0 && plain + 1.0 < hard,
+            "{easy} {plain} {hard}"
+        );
+
+        // 0 is exactly the unweighted sampling
+        l.set_difficulty(0.0);
+        assert_eq!(l.sample(50, 1).collect::<Vec<String>>(), uniform);
+    }
+
+    #[test]
+    fn test_inorder() {
+        let mut l = Lang::from_words("test", ["a", "b", "c"].map(String::from).to_vec());
+        l.inorder = true;
+        assert_eq!(l.gen_words(2, 0).collect::<Vec<String>>(), vec!["a", "b"]);
+        // words that weren't typed come again
+        assert_eq!(l.gen_words(2, 0).collect::<Vec<String>>(), vec!["a", "b"]);
+        l.advance_inorder(2);
+        assert_eq!(l.gen_words(2, 0).collect::<Vec<String>>(), vec!["c", "a"]);
+        l.advance_inorder(2);
+        // more words than the list wraps as many times as needed
+        assert_eq!(
+            l.gen_words(7, 0).collect::<Vec<String>>(),
+            vec!["b", "c", "a", "b", "c", "a", "b"]
+        );
+        l.advance_inorder(7);
+        assert_eq!(l.inorder_index, 2);
+
+        // lines of several words are split, continuing mid-line
+        let mut l = Lang::from_words(
+            "quotes",
+            vec!["Hello,  world!".to_string(), "Bye.".to_string()],
+        );
+        l.inorder = true;
+        l.punctuated = true;
+        assert_eq!(l.gen_words(1, 0).collect::<Vec<String>>(), vec!["Hello,"]);
+        l.advance_inorder(1);
+        assert_eq!(
+            l.gen_words(3, 0).collect::<Vec<String>>(),
+            vec!["world!", "Bye.", "Hello,"]
+        );
+    }
+
+    #[test]
+    fn test_select_one() {
+        let mut l = Lang::from_words(
+            "quotes",
+            vec![
+                "To be, or not to be.".to_string(),
+                "Hello  there".to_string(),
+            ],
+        );
+        l.select_one = true;
+        for seed in 0..10 {
+            let words = l.gen_words(50, seed).collect::<Vec<String>>();
+            assert!(
+                words == vec!["To", "be,", "or", "not", "to", "be."]
+                    || words == vec!["Hello", "there"]
+            );
+        }
+        // sampling splits lines into words
+        assert!(l.sample(20, 0).all(|w| !w.contains(' ')));
+        assert_eq!(l.sample(20, 0).count(), 20);
+    }
+
+    #[test]
+    fn test_select_all() {
+        let mut l = Lang::from_words("curated", (0..40).map(|i| format!("w{i}")).collect());
+        l.select_all = true;
+        let mut words = l.gen_words(5, 1).collect::<Vec<String>>();
+        assert_eq!(words.len(), l.words.len());
+        assert_ne!(words, l.words);
+        words.sort();
+        let mut all = l.words.clone();
+        all.sort();
+        assert_eq!(words, all);
+
+        // punctuated lines are split into words, each line kept in order
+        let mut l = Lang::from_words(
+            "quotes",
+            vec!["To be, or not.".to_string(), "Hello  there".to_string()],
+        );
+        l.select_all = true;
+        l.punctuated = true;
+        for seed in 0..10 {
+            let words = l.gen_words(5, seed).collect::<Vec<String>>();
+            assert_eq!(words.len(), 6);
+            assert!(words.iter().all(|w| !w.contains(' ')));
+            let i = words.iter().position(|w| w == "To").unwrap();
+            assert_eq!(words[i..i + 4], ["To", "be,", "or", "not."]);
+        }
+    }
+
+    #[test]
+    fn test_seeded_words() {
+        let l = Lang::from_words("test", ('a'..='z').map(|c| c.to_string()).collect());
+        let a = l.gen_words(50, 1234).collect::<Vec<String>>();
+        assert_eq!(a, l.gen_words(50, 1234).collect::<Vec<String>>());
+        assert_ne!(a, l.gen_words(50, 4321).collect::<Vec<String>>());
+    }
+}
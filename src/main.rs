@@ -1,16 +1,22 @@
 //! arstyper
+mod bench;
+mod calibration;
+mod color_preview;
 mod config;
 mod lang;
+mod nemesis;
+mod practice;
+mod recovery;
+mod results;
+mod status;
+mod streak;
 mod test;
 mod ui;
-mod color_preview;
 
 use config::Config;
 use ui::Ui;
 
 fn main() -> std::io::Result<()> {
     let cfg = Config::get()?;
-    let ui = Ui::new(cfg)?;
-    ui.run()?;
-    Ok(())
+    Ui::new(cfg)?.run()
 }
@@ -0,0 +1,95 @@
+//! Words missed most often over all of history, for tests of one's worst words
+use crate::results;
+use rand::{SeedableRng, rngs::StdRng, seq::SliceRandom};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, HashSet},
+    io,
+    path::PathBuf,
+};
+
+/// Most words kept, those missed least are dropped past this
+const MAX_WORDS: usize = 1000;
+
+/// How many of the most missed words tests are built from
+const POOL: usize = 50;
+
+/// Times each word has been missed, persisted under the data dir.
+#[derive(Deserialize, Serialize, Default)]
+pub struct Nemesis {
+    misses: BTreeMap<String, u32>,
+}
+
+impl Nemesis {
+    /// Path to the missed word file.
+    pub fn path() -> PathBuf {
+        results::dir().join("nemesis.json")
+    }
+
+    /// Load the missed words, empty if none have been recorded yet.
+    pub fn load() -> io::Result<Self> {
+        Ok(results::load_json(&Self::path())?.unwrap_or_default())
+    }
+
+    /// Save the missed words.
+    pub fn save(&self) -> io::Result<()> {
+        results::save_json(&Self::path(), self)
+    }
+
+    /// Count the missed words of a completed test, given each word and whether it was typed correctly
+    pub fn record<'a>(&mut self, words: impl Iterator<Item = (&'a str, bool)>) {
+        for (w, _) in words.filter(|(_, correct)| !correct) {
+            *self.misses.entry(w.to_lowercase()).or_default() += 1;
+        }
+        if self.misses.len() > MAX_WORDS {
+            let keep = self
+                .top(MAX_WORDS)
+                .into_iter()
+                .map(String::from)
+                .collect::<HashSet<String>>();
+            self.misses.retain(|w, _| keep.contains(w));
+        }
+    }
+
+    /// Up to `n` words missed most often, most first
+    fn top(&self, n: usize) -> Vec<&str> {
+        let mut words = self.misses.iter().collect::<Vec<(&String, &u32)>>();
+        // stable, so ties stay alphabetical
+        words.sort_by(|a, b| b.1.cmp(a.1));
+        words.into_iter().take(n).map(|(w, _)| w.as_str()).collect()
+    }
+
+    /// Generate `n` words from the most missed, cycling through them in a shuffled order.
+    /// Empty if no words have been missed.
+    pub fn gen_words(&self, n: usize, seed: u64) -> Vec<String> {
+        let mut pool = self.top(POOL);
+        if pool.is_empty() {
+            return Vec::new();
+        }
+        pool.shuffle(&mut StdRng::seed_from_u64(seed));
+        pool.iter().cycle().take(n).map(|w| w.to_string()).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_top_missed() {
+        let mut m = Nemesis::default();
+        assert!(m.gen_words(3, 0).is_empty());
+        m.record([("The", false), ("quick", false), ("fox", true)].into_iter());
+        m.record([("the", false)].into_iter());
+        assert_eq!(m.top(5), vec!["the", "quick"]);
+        assert_eq!(m.gen_words(4, 0).len(), 4);
+
+        // the least missed are dropped past the cap
+        for i in 0..MAX_WORDS {
+            m.record([(format!("w{i}").as_str(), false)].into_iter());
+        }
+        assert_eq!(m.misses.len(), MAX_WORDS);
+        assert_eq!(m.top(1), vec!["the"]);
+        assert!(m.misses.contains_key("quick"));
+    }
+}
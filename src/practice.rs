@@ -0,0 +1,154 @@
+//! Practice queue of problem words, scheduled with spaced repetition
+//!
+//! Each word sits in a Leitner-style level. Missing a word drops it to level 0, due again next test.
+//! Typing it correctly while due moves it up a level, doubling the number of tests until it is due again,
+//! until it is mastered and leaves the queue.
+use crate::results;
+use rand::{SeedableRng, rngs::StdRng, seq::SliceRandom};
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, io, path::PathBuf};
+
+/// Words correctly typed at this level are mastered and removed from the queue
+const MASTERED_LEVEL: u32 = 5;
+
+/// Scheduling state of one problem word
+#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, Debug)]
+struct Entry {
+    /// Spaced repetition level, the word is due every 2^level tests
+    level: u32,
+    /// Test number from which the word is due
+    due: u64,
+}
+
+/// Queue of problem words, persisted under the data dir.
+#[derive(Deserialize, Serialize, Default)]
+pub struct Practice {
+    /// Tests recorded so far, the clock for scheduling
+    tests: u64,
+    words: BTreeMap<String, Entry>,
+}
+
+impl Practice {
+    /// Path to the practice queue file.
+    pub fn path() -> PathBuf {
+        results::dir().join("practice.json")
+    }
+
+    /// Load the practice queue, empty if it doesn't exist yet.
+    pub fn load() -> io::Result<Self> {
+        Ok(results::load_json(&Self::path())?.unwrap_or_default())
+    }
+
+    /// Save the practice queue.
+    pub fn save(&self) -> io::Result<()> {
+        results::save_json(&Self::path(), self)
+    }
+
+    /// Number of words in the queue
+    pub fn len(&self) -> usize {
+        self.words.len()
+    }
+
+    /// Add a word to the queue, due immediately. Words already queued are reset.
+    pub fn add(&mut self, word: &str) {
+        self.words.insert(
+            word.to_lowercase(),
+            Entry {
+                level: 0,
+                due: self.tests,
+            },
+        );
+    }
+
+    /// Update the queue from the words of a completed test and whether each was typed correctly.
+    /// Missed words are added, and due words typed correctly are scheduled further out.
+    pub fn record<'a>(&mut self, words: impl Iterator<Item = (&'a str, bool)>) {
+        self.tests += 1;
+        for (w, correct) in words {
+            let w = w.to_lowercase();
+            if !correct {
+                self.words.insert(
+                    w,
+                    Entry {
+                        level: 0,
+                        due: self.tests,
+                    },
+                );
+                continue;
+            }
+            let Some(e) = self.words.get_mut(&w) else {
+                continue;
+            };
+            // only count practice once the word is due, so typing it twice in a test doesn't promote it twice
+            if e.due > self.tests {
+                continue;
+            }
+            e.level += 1;
+            if e.level >= MASTERED_LEVEL {
+                self.words.remove(&w);
+            } else {
+                e.due = self.tests + (1 << e.level);
+            }
+        }
+    }
+
+    /// Words currently due for practice
+    fn due(&self) -> Vec<&str> {
+        self.words
+            .iter()
+            .filter(|(_, e)| e.due <= self.tests)
+            .map(|(w, _)| w.as_str())
+            .collect()
+    }
+
+    /// Generate `n` words from the due set, cycling through it in a shuffled order.
+    /// When nothing is due, the words due soonest are used instead. Empty if the queue is.
+    pub fn gen_words(&self, n: usize, seed: u64) -> Vec<String> {
+        let mut pool = self.due();
+        if pool.is_empty() {
+            let soonest = self.words.values().map(|e| e.due).min().unwrap_or(0);
+            pool = self
+                .words
+                .iter()
+                .filter(|(_, e)| e.due == soonest)
+                .map(|(w, _)| w.as_str())
+                .collect();
+        }
+        if pool.is_empty() {
+            return Vec::new();
+        }
+        pool.shuffle(&mut StdRng::seed_from_u64(seed));
+        pool.iter().cycle().take(n).map(|w| w.to_string()).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_schedule() {
+        let mut p = Practice::default();
+        p.record([("the", true), ("quick", false)].into_iter());
+        assert_eq!(p.due(), vec!["quick"]);
+
+        // correct while due moves it 2 tests out
+        p.record([("quick", true)].into_iter());
+        assert!(p.due().is_empty());
+        assert_eq!(p.gen_words(3, 0), vec!["quick"; 3]);
+        p.record(std::iter::empty());
+        p.record(std::iter::empty());
+        assert_eq!(p.due(), vec!["quick"]);
+
+        // missing it again starts over
+        p.record([("quick", false)].into_iter());
+        assert_eq!(p.words["quick"].level, 0);
+
+        // mastered words leave the queue
+        for _ in 0..100 {
+            p.record([("quick", true)].into_iter());
+        }
+        assert_eq!(p.len(), 0);
+        assert!(p.gen_words(3, 0).is_empty());
+    }
+}
@@ -0,0 +1,134 @@
+//! Autosave of the test in progress, so an interrupted session can be picked up again
+use crate::{
+    lang::Lang,
+    results::{self, Mode, Record},
+};
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Deserializer, Serialize};
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+/// Snapshot of an unfinished test: the result so far, and the words still to type.
+#[derive(Deserialize, Serialize, Clone, PartialEq, Debug)]
+pub struct Recovery {
+    /// When the snapshot was taken
+    pub saved: DateTime<Local>,
+    /// Result of the part already typed, recorded when recovering
+    pub record: Record,
+    /// Words from the one in progress on, as generated
+    pub words: Vec<String>,
+    /// Words keep their case
+    pub punctuated: bool,
+    /// Keys typed of the word in progress, replayed on resuming. Unreadable keys leave it untyped
+    #[serde(default, deserialize_with = "lenient_keys")]
+    pub partial: Vec<char>,
+    /// The typed part is already in history, so isn't recorded again on resuming
+    #[serde(default)]
+    pub recorded: bool,
+}
+
+/// Keys of the word in progress, empty if they can't be read so the word is typed from its start
+fn lenient_keys<'de, D: Deserializer<'de>>(d: D) -> Result<Vec<char>, D::Error> {
+    let v = serde_json::Value::deserialize(d)?;
+    Ok(serde_json::from_value(v).unwrap_or_default())
+}
+
+impl Recovery {
+    /// Is this the place in an `inorder` test of `lang`, which continues without asking.
+    pub fn continues(&self, lang: &Lang) -> bool {
+        lang.inorder && self.record.mode == Mode::Words && self.record.lang == lang.name
+    }
+
+    /// Path to the recovery file.
+    pub fn path() -> PathBuf {
+        results::dir().join("recovery.json")
+    }
+
+    /// Load the snapshot of an interrupted test, None if there is none.
+    pub fn load() -> io::Result<Option<Self>> {
+        results::load_json(&Self::path())
+    }
+
+    /// Save the snapshot, replacing any previous one.
+    pub fn save(&self) -> io::Result<()> {
+        results::save_json(&Self::path(), self)
+    }
+
+    /// Remove the snapshot, such as once its test has ended normally.
+    pub fn clear() -> io::Result<()> {
+        Self::clear_at(&Self::path())
+    }
+
+    fn clear_at(p: &Path) -> io::Result<()> {
+        match fs::remove_file(p) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clear_recovery() {
+        let p = std::env::temp_dir()
+            .join(format!("arstyper-recovery-{}", std::process::id()))
+            .join("recovery.json");
+        fs::create_dir_all(p.parent().unwrap()).unwrap();
+        fs::write(&p, "{}").unwrap();
+        Recovery::clear_at(&p).unwrap();
+        let cleared = p.exists();
+        // clearing twice is fine
+        Recovery::clear_at(&p).unwrap();
+        let _ = fs::remove_dir_all(p.parent().unwrap());
+        assert!(!cleared);
+    }
+
+    #[test]
+    fn test_partial_keys() {
+        let r = Recovery {
+            saved: Local::now(),
+            record: Record {
+                timestamp: Local::now(),
+                lang: "english".to_string(),
+                label: String::new(),
+                word_count: 1,
+                correct_chars: 5,
+                secs: 2.0,
+                accuracy: Some(100.0),
+                mode: Default::default(),
+                partial: true,
+            },
+            words: vec!["word".to_string()],
+            punctuated: false,
+            partial: vec!['w', 'x', crate::test::BKSPC],
+            recorded: true,
+        };
+        let json = serde_json::to_string(&r).unwrap();
+        assert_eq!(serde_json::from_str::<Recovery>(&json).unwrap(), r);
+
+        // only the place in an inorder test of the same language continues without asking
+        let mut l = Lang::from_words("english", vec!["word".to_string()]);
+        assert!(!r.continues(&l));
+        l.inorder = true;
+        assert!(r.continues(&l));
+        assert!(!r.continues(&Lang::from_words("german", Vec::new())));
+
+        // corrupt keys start the word over, keeping the rest of the snapshot
+        let mut v = serde_json::to_value(&r).unwrap();
+        v["partial"] = serde_json::json!(["w", 3, "long"]);
+        let corrupt: Recovery = serde_json::from_value(v.clone()).unwrap();
+        assert!(corrupt.partial.is_empty());
+        assert_eq!(corrupt.words, r.words);
+
+        // snapshots from before partial words
+        v.as_object_mut().unwrap().remove("partial");
+        v.as_object_mut().unwrap().remove("recorded");
+        let old: Recovery = serde_json::from_value(v).unwrap();
+        assert!(old.partial.is_empty() && !old.recorded);
+    }
+}
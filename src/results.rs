@@ -0,0 +1,393 @@
+//! Persisting and loading of completed test results
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{self, BufRead, Write},
+    path::{Path, PathBuf},
+};
+use strum::Display;
+
+/// A single completed test, stored as one line of JSON in the history file.
+#[derive(Deserialize, Serialize, Clone, PartialEq, Debug)]
+pub struct Record {
+    /// When the test was completed
+    pub timestamp: DateTime<Local>,
+    /// Language name
+    pub lang: String,
+    /// Session label, empty if none
+    #[serde(default)]
+    pub label: String,
+    /// How many words were tested
+    pub word_count: usize,
+    /// Correctly typed characters, including the spaces between words
+    pub correct_chars: usize,
+    /// Seconds from the first to the last keypress
+    pub secs: f64,
+    /// Percentage of correct keypresses, none for records from before it was kept
+    #[serde(default)]
+    pub accuracy: Option<f64>,
+    /// Where the words came from
+    #[serde(default)]
+    pub mode: Mode,
+    /// Quit before it was completed, so left out of bests and averages
+    #[serde(default)]
+    pub partial: bool,
+}
+
+/// Source of a test's words
+#[derive(Deserialize, Serialize, Clone, Copy, Default, PartialEq, Debug, Display)]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+pub enum Mode {
+    /// Generated from the language
+    #[default]
+    Words,
+    /// The practice queue of missed words
+    Practice,
+    /// The words missed most often over all of history
+    Nemesis,
+    /// Words given by the caller
+    Custom,
+    /// An endless stream of words from the language, never recorded
+    Zen,
+}
+
+/// Unit that typing speeds are displayed in
+#[derive(Deserialize, Serialize, Clone, Copy, Default, Display)]
+#[serde(rename_all = "lowercase")]
+pub enum SpeedUnit {
+    /// Words per minute, where a word is 5 characters
+    #[default]
+    #[strum(to_string = "WPM")]
+    Wpm,
+    /// Characters per minute
+    #[strum(to_string = "CPM")]
+    Cpm,
+}
+
+impl SpeedUnit {
+    /// Speed in this unit from correct characters over seconds, 0 if no time has passed
+    pub fn speed(self, chars: usize, secs: f64) -> f64 {
+        if secs <= 0.0 {
+            return 0.0;
+        }
+        let cpm = chars as f64 / secs * 60.0;
+        match self {
+            Self::Wpm => cpm / 5.0,
+            Self::Cpm => cpm,
+        }
+    }
+
+    /// Speed in this unit from words per minute
+    pub fn of_wpm(self, wpm: f64) -> f64 {
+        match self {
+            Self::Wpm => wpm,
+            Self::Cpm => wpm * 5.0,
+        }
+    }
+
+    /// Formatted speed with unit label, such as "72.4 WPM"
+    pub fn format(self, chars: usize, secs: f64) -> String {
+        format!("{:.1} {self}", self.speed(chars, secs))
+    }
+}
+
+/// Directory for history and other user data.
+/// Tests get a scratch directory instead, so they never read or write the user's data.
+pub fn dir() -> PathBuf {
+    if cfg!(test) {
+        return std::env::temp_dir().join(format!("arstyper-userdata-{}", std::process::id()));
+    }
+    dirs::data_local_dir()
+        .unwrap()
+        .join("arstyper")
+        .join("userdata")
+}
+
+/// Load user data saved as JSON, None if it hasn't been saved yet.
+pub fn load_json<T: DeserializeOwned>(p: &Path) -> io::Result<Option<T>> {
+    match fs::read_to_string(p) {
+        Ok(s) => Ok(Some(serde_json::from_str(&s)?)),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+/// Save user data as JSON, replacing any saved before.
+pub fn save_json<T: Serialize>(p: &Path, v: &T) -> io::Result<()> {
+    fs::create_dir_all(p.parent().unwrap())?;
+    // write then rename, so a crash mid-write never leaves a truncated file
+    let tmp = p.with_extension("json.tmp");
+    fs::write(&tmp, serde_json::to_string(v)?)?;
+    fs::rename(tmp, p)
+}
+
+/// Path to the history file.
+pub fn path() -> PathBuf {
+    dir().join("history.jsonl")
+}
+
+/// Load all records from the history file.
+pub fn load() -> io::Result<Vec<Record>> {
+    load_from(&path())
+}
+
+/// Load all records from a history file. Missing files are treated as empty history and
+/// malformed lines are skipped.
+fn load_from(p: &PathBuf) -> io::Result<Vec<Record>> {
+    let f = match File::open(p) {
+        Ok(f) => f,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+
+    Ok(io::BufReader::new(f)
+        .lines()
+        .map_while(Result::ok)
+        .filter_map(|l| serde_json::from_str(&l).ok())
+        .collect())
+}
+
+/// Append a record to the history file, creating it if needed.
+/// If the history would exceed `max` records, the oldest are dropped and the file is rewritten,
+/// so a `max` of 0 keeps nothing.
+pub fn append(r: &Record, max: usize) -> io::Result<()> {
+    append_to(&path(), r, max)
+}
+
+fn append_to(p: &PathBuf, r: &Record, max: usize) -> io::Result<()> {
+    fs::create_dir_all(p.parent().unwrap())?;
+
+    let mut records = load_from(p)?;
+    if records.len() < max {
+        let mut f = OpenOptions::new().create(true).append(true).open(p)?;
+        return writeln!(f, "{}", serde_json::to_string(r)?);
+    }
+
+    // rotate, writing then renaming so a crash mid-write never loses the history
+    records.push(r.clone());
+    let excess = records.len().saturating_sub(max);
+    let tmp = p.with_extension("jsonl.tmp");
+    let mut f = File::create(&tmp)?;
+    for r in records.iter().skip(excess) {
+        writeln!(f, "{}", serde_json::to_string(r)?)?;
+    }
+    fs::rename(tmp, p)
+}
+
+/// Aggregate statistics over the completed tests of some records.
+pub struct Summary<'a> {
+    pub tests: usize,
+    /// Mean speed of all tests
+    pub avg_speed: f64,
+    /// Fastest test
+    pub best: &'a Record,
+    /// Mean accuracy of the tests that recorded it, None if none did
+    pub avg_accuracy: Option<f64>,
+    /// Total seconds spent typing
+    pub secs: f64,
+}
+
+impl<'a> Summary<'a> {
+    /// Summarize records with speeds in `unit`, None if there are no completed tests.
+    pub fn of(records: &[&'a Record], unit: SpeedUnit) -> Option<Self> {
+        let records = records
+            .iter()
+            .filter(|r| !r.partial)
+            .copied()
+            .collect::<Vec<&Record>>();
+        let speed = |r: &Record| unit.speed(r.correct_chars, r.secs);
+        let best = *records
+            .iter()
+            .max_by(|a, b| speed(a).total_cmp(&speed(b)))?;
+        let accuracies = records
+            .iter()
+            .filter_map(|r| r.accuracy)
+            .collect::<Vec<f64>>();
+        Some(Self {
+            tests: records.len(),
+            avg_speed: records.iter().map(|r| speed(r)).sum::<f64>() / records.len() as f64,
+            best,
+            avg_accuracy: (!accuracies.is_empty())
+                .then(|| accuracies.iter().sum::<f64>() / accuracies.len() as f64),
+            secs: records.iter().map(|r| r.secs).sum(),
+        })
+    }
+}
+
+/// Write a human-readable Markdown report of `records` to `p`.
+pub fn export_markdown(p: &Path, records: &[&Record], unit: SpeedUnit) -> io::Result<()> {
+    if let Some(d) = p.parent() {
+        fs::create_dir_all(d)?;
+    }
+    fs::write(p, markdown(records, unit))
+}
+
+/// Number of most recent tests listed in reports
+const REPORT_RECENT: usize = 20;
+
+/// Markdown report of summary statistics, best result, and most recent tests.
+fn markdown(records: &[&Record], unit: SpeedUnit) -> String {
+    let mut s = format!(
+        "# arstyper report\n\nGenerated {}\n\n",
+        Local::now().format("%Y-%m-%d %H:%M")
+    );
+    if records.is_empty() {
+        s.push_str("No tests recorded yet.\n");
+        return s;
+    }
+
+    let speed = |r: &Record| unit.speed(r.correct_chars, r.secs);
+    // no summary while only partial tests have been recorded
+    if let Some(sum) = Summary::of(records, unit) {
+        let secs = sum.secs as u64;
+        s.push_str("## Summary\n\n");
+        s.push_str(&format!("- Tests: {}\n", sum.tests));
+        s.push_str(&format!("- Average speed: {:.1} {unit}\n", sum.avg_speed));
+        s.push_str(&format!(
+            "- Best speed: {} ({}, {})\n",
+            unit.format(sum.best.correct_chars, sum.best.secs),
+            sum.best.lang,
+            sum.best.timestamp.format("%Y-%m-%d")
+        ));
+        if let Some(a) = sum.avg_accuracy {
+            s.push_str(&format!("- Average accuracy: {a:.1}%\n"));
+        }
+        s.push_str(&format!("- Time typing: {}m {}s\n\n", secs / 60, secs % 60));
+    }
+
+    s.push_str("## Recent tests\n\n");
+    s.push_str(&format!("| Date | Language | Label | Words | {unit} |\n"));
+    s.push_str("|---|---|---|---|---|\n");
+    for r in records.iter().rev().take(REPORT_RECENT) {
+        s.push_str(&format!(
+            "| {} | {} | {} | {} | {:.1} |\n",
+            r.timestamp.format("%Y-%m-%d %H:%M"),
+            r.lang,
+            r.label,
+            if r.partial {
+                format!("{} (partial)", r.word_count)
+            } else {
+                r.word_count.to_string()
+            },
+            speed(r)
+        ));
+    }
+    s
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_append_rotates() {
+        let p = std::env::temp_dir()
+            .join(format!("arstyper-test-{}", std::process::id()))
+            .join("history.jsonl");
+        let _ = fs::remove_file(&p);
+
+        for i in 0..5 {
+            let r = Record {
+                timestamp: Local::now(),
+                lang: "english".to_string(),
+                label: "".to_string(),
+                word_count: i,
+                correct_chars: 0,
+                secs: 0.0,
+                accuracy: None,
+                mode: Mode::Words,
+                partial: false,
+            };
+            append_to(&p, &r, 3).unwrap();
+        }
+
+        let records = load_from(&p).unwrap();
+        assert_eq!(
+            records.iter().map(|r| r.word_count).collect::<Vec<usize>>(),
+            vec![2, 3, 4]
+        );
+        assert!(!p.with_extension("jsonl.tmp").exists());
+
+        // keeping nothing
+        append_to(&p, &records[0], 0).unwrap();
+        let emptied = load_from(&p).unwrap();
+        let _ = fs::remove_dir_all(p.parent().unwrap());
+        assert!(emptied.is_empty());
+    }
+
+    #[test]
+    fn test_persist_json() {
+        let p = std::env::temp_dir()
+            .join(format!("arstyper-json-{}", std::process::id()))
+            .join("data.json");
+        assert_eq!(load_json::<Vec<u32>>(&p).unwrap(), None);
+        save_json(&p, &vec![1, 2]).unwrap();
+        save_json(&p, &vec![3]).unwrap();
+        let loaded = load_json::<Vec<u32>>(&p).unwrap();
+        let _ = fs::remove_dir_all(p.parent().unwrap());
+        assert_eq!(loaded, Some(vec![3]));
+    }
+
+    #[test]
+    fn test_load_old_records() {
+        let r: Record = serde_json::from_str(
+            r#"{"timestamp":"2024-02-01T10:00:00+01:00","lang":"english","word_count":10,"correct_chars":50,"secs":10.0}"#,
+        )
+        .unwrap();
+        assert_eq!(r.accuracy, None);
+        assert!(!r.partial);
+        assert_eq!(r.mode, Mode::Words);
+
+        let r: Record = serde_json::from_str(
+            r#"{"timestamp":"2024-02-01T10:00:00+01:00","lang":"english","word_count":10,"correct_chars":50,"secs":10.0,"accuracy":97.5,"mode":"practice"}"#,
+        )
+        .unwrap();
+        assert_eq!(r.accuracy, Some(97.5));
+        assert_eq!(r.mode, Mode::Practice);
+    }
+
+    #[test]
+    fn test_speed_units() {
+        assert_eq!(SpeedUnit::Wpm.speed(50, 10.0), 60.0);
+        assert_eq!(SpeedUnit::Cpm.speed(50, 10.0), 300.0);
+        assert_eq!(SpeedUnit::Wpm.speed(50, 0.0), 0.0);
+        assert_eq!(SpeedUnit::Cpm.format(50, 10.0), "300.0 CPM");
+    }
+
+    #[test]
+    fn test_markdown() {
+        assert!(markdown(&[], SpeedUnit::Wpm).contains("No tests recorded yet."));
+
+        let r = Record {
+            timestamp: Local::now(),
+            lang: "english".to_string(),
+            label: "drill".to_string(),
+            word_count: 10,
+            correct_chars: 50,
+            secs: 10.0,
+            accuracy: Some(100.0),
+            mode: Mode::Words,
+            partial: false,
+        };
+        let md = markdown(&[&r, &r], SpeedUnit::Wpm);
+        assert!(md.contains("- Tests: 2"));
+        assert!(md.contains("- Average accuracy: 100.0%"));
+        assert!(md.contains("- Best speed: 60.0 WPM (english"));
+        assert!(md.contains("| english | drill | 10 | 60.0 |"));
+
+        // partial tests are listed, but not summarized
+        let quit = Record {
+            word_count: 4,
+            correct_chars: 100,
+            partial: true,
+            ..r.clone()
+        };
+        let md = markdown(&[&r, &quit], SpeedUnit::Wpm);
+        assert!(md.contains("- Tests: 1"));
+        assert!(md.contains("- Best speed: 60.0 WPM"));
+        assert!(md.contains("| english | drill | 4 (partial) | 120.0 |"));
+        assert!(!markdown(&[&quit], SpeedUnit::Wpm).contains("## Summary"));
+    }
+}
@@ -0,0 +1,123 @@
+//! Statusbar messages with priorities
+use chrono::{DateTime, Local, TimeDelta};
+
+/// How important a status message is. Higher priorities are shown first and are never replaced by lower ones.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Priority {
+    Info,
+    Warn,
+    Error,
+}
+
+struct Message {
+    priority: Priority,
+    text: String,
+    /// How long to show the message for once displayed
+    duration: TimeDelta,
+}
+
+/// Currently displayed status message and those waiting to be shown.
+#[derive(Default)]
+pub struct StatusBar {
+    /// Displayed message and when it is to be cleared
+    current: Option<(Message, DateTime<Local>)>,
+    queue: Vec<Message>,
+}
+
+impl StatusBar {
+    /// Show a message for `duration`. Preempts the current message if of higher priority, which is requeued.
+    /// Otherwise it is queued behind messages of equal or higher priority.
+    pub fn push(&mut self, priority: Priority, text: String, duration: TimeDelta) {
+        let m = Message {
+            priority,
+            text,
+            duration,
+        };
+        match self.current.take() {
+            None => self.show(m),
+            Some((cur, until)) => {
+                if priority > cur.priority {
+                    self.queue.insert(0, cur);
+                    self.show(m);
+                } else {
+                    self.current = Some((cur, until));
+                    self.queue.push(m);
+                }
+            }
+        }
+    }
+
+    /// Clear the current message if it has expired, showing the next one.
+    pub fn tick(&mut self) {
+        if let Some((_, until)) = &self.current
+            && Local::now() >= *until
+        {
+            self.dismiss();
+        }
+    }
+
+    /// Clear the current message, showing the next one.
+    pub fn dismiss(&mut self) {
+        self.current = None;
+        // first of the highest priority
+        let next = self
+            .queue
+            .iter()
+            .enumerate()
+            .rev()
+            .max_by_key(|(_, m)| m.priority)
+            .map(|(i, _)| i);
+        if let Some(i) = next {
+            let m = self.queue.remove(i);
+            self.show(m);
+        }
+    }
+
+    /// Currently displayed message, if any
+    pub fn text(&self) -> Option<&str> {
+        self.current.as_ref().map(|(m, _)| m.text.as_str())
+    }
+
+    fn show(&mut self, m: Message) {
+        let until = Local::now() + m.duration;
+        self.current = Some((m, until));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_priority() {
+        let mut s = StatusBar::default();
+        s.push(Priority::Error, "error".to_string(), TimeDelta::seconds(5));
+        s.push(Priority::Info, "info 1".to_string(), TimeDelta::seconds(5));
+        s.push(Priority::Info, "info 2".to_string(), TimeDelta::seconds(5));
+        assert_eq!(s.text(), Some("error"));
+
+        s.dismiss();
+        assert_eq!(s.text(), Some("info 1"));
+
+        // preempted message is shown again afterwards
+        s.push(Priority::Warn, "warn".to_string(), TimeDelta::seconds(5));
+        assert_eq!(s.text(), Some("warn"));
+        s.dismiss();
+        assert_eq!(s.text(), Some("info 1"));
+        s.dismiss();
+        assert_eq!(s.text(), Some("info 2"));
+        s.dismiss();
+        assert_eq!(s.text(), None);
+    }
+
+    #[test]
+    fn test_tick_expires() {
+        let mut s = StatusBar::default();
+        s.push(Priority::Info, "a".to_string(), TimeDelta::zero());
+        s.push(Priority::Info, "b".to_string(), TimeDelta::seconds(5));
+        s.tick();
+        assert_eq!(s.text(), Some("b"));
+        s.tick();
+        assert_eq!(s.text(), Some("b"));
+    }
+}
@@ -0,0 +1,82 @@
+//! Consecutive days with at least one completed test
+use crate::results;
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::{io, path::PathBuf};
+
+/// Practice streak, persisted under the data dir. Days are local dates, so a streak follows the user's calendar.
+#[derive(Deserialize, Serialize, Clone, Copy, Default, PartialEq, Debug)]
+pub struct Streak {
+    /// Consecutive days practiced, ending on `last`
+    days: u32,
+    /// Last day a test was completed
+    last: Option<NaiveDate>,
+}
+
+impl Streak {
+    /// Path to the streak file.
+    pub fn path() -> PathBuf {
+        results::dir().join("streak.json")
+    }
+
+    /// Load the streak, empty if there is none yet.
+    pub fn load() -> io::Result<Self> {
+        Ok(results::load_json(&Self::path())?.unwrap_or_default())
+    }
+
+    /// Save the streak.
+    pub fn save(&self) -> io::Result<()> {
+        results::save_json(&Self::path(), self)
+    }
+
+    /// Streak as of `today`. Still running if the last practice was yesterday, 0 if a day was missed
+    pub fn current(&self, today: NaiveDate) -> u32 {
+        match self.last {
+            Some(d) if d == today || d.succ_opt() == Some(today) => self.days,
+            _ => 0,
+        }
+    }
+
+    /// Count a test completed `today`, returning whether the streak changed
+    pub fn record(&mut self, today: NaiveDate) -> bool {
+        if self.last == Some(today) {
+            return false;
+        }
+        self.days = self.current(today) + 1;
+        self.last = Some(today);
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn day(d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 2, d).unwrap()
+    }
+
+    #[test]
+    fn test_streak() {
+        let mut s = Streak::default();
+        assert_eq!(s.current(day(1)), 0);
+        assert!(s.record(day(1)));
+        assert!(!s.record(day(1)));
+        assert_eq!(s.current(day(1)), 1);
+
+        // across the end of the month
+        let mut s = Streak {
+            days: 4,
+            last: Some(day(29)),
+        };
+        let march = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+        assert_eq!(s.current(march), 4);
+        s.record(march);
+        assert_eq!(s.current(march), 5);
+
+        // a missed day resets it
+        assert_eq!(s.current(NaiveDate::from_ymd_opt(2024, 3, 3).unwrap()), 0);
+        s.record(NaiveDate::from_ymd_opt(2024, 3, 3).unwrap());
+        assert_eq!(s.days, 1);
+    }
+}
@@ -1,57 +1,319 @@
 //! Typing test struct
-use crate::ui::{Screen, Styles, UiRequest};
+use crate::{
+    config::{KeybindsCfg, TestCfg},
+    results::SpeedUnit,
+    status::Priority,
+    ui::{Screen, Styles, UiRequest},
+};
+use chrono::TimeDelta;
+use rand::{RngExt, SeedableRng, rngs::StdRng};
 
 use ratatui::{
-    buffer::Buffer,
+    buffer::{Buffer, Cell},
     crossterm::event::{KeyCode, KeyEvent, KeyModifiers},
     layout::Rect,
-    style::Stylize,
+    style::{Modifier, Stylize},
     text::{Line, Span},
     widgets::{Block, Borders, Padding, Paragraph, Widget, Wrap},
 };
-use std::{cmp::min, sync::mpsc::SyncSender, time::Instant};
+use serde::{Deserialize, Serialize};
+use std::{
+    cmp::{max, min},
+    collections::VecDeque,
+    ops::{AddAssign, Range},
+    sync::mpsc::SyncSender,
+    time::{Duration, Instant},
+};
+use unicode_width::UnicodeWidthStr;
+
+/// Window of recent keypresses that keystrokes per second are measured over
+const KPS_WINDOW: Duration = Duration::from_secs(2);
+
+/// Character presses before live accuracy is checked, so a single early mistake doesn't abort
+const MIN_ACCURACY_PRESSES: usize = 20;
+
+/// Most recent mistakes kept for the error ticker
+const RECENT_ERRORS: usize = 4;
+
+/// Shown in place of untyped characters when obscured
+const OBSCURED: char = '·';
+
+/// Typed words kept behind the current one in endless tests, older ones are dropped
+const ENDLESS_KEPT: usize = 100;
 
 /// A normal backspace
 pub const BKSPC: char = 0x08 as char;
 /// A "backspace" for deleting an entire word
 pub const WORD_BKSPC: char = 0x18 as char;
 
+/// Parse a key such as `ctrl+s`, `alt+n`, `f5`, `esc`, `space`, `left`, or a single character. None if empty or invalid.
+/// Names and modifiers are case insensitive, as are characters held with ctrl or alt, which terminals
+/// can't tell apart. Other characters keep their case, so `Q` is Shift+q.
+pub fn parse_key(s: &str) -> Option<(KeyCode, KeyModifiers)> {
+    let mut mods = KeyModifiers::NONE;
+    let mut key = s;
+    while let Some((m, rest)) = key.split_once('+').filter(|(_, rest)| !rest.is_empty()) {
+        mods |= match m.to_lowercase().as_str() {
+            "ctrl" => KeyModifiers::CONTROL,
+            "alt" => KeyModifiers::ALT,
+            "shift" => KeyModifiers::SHIFT,
+            _ => return None,
+        };
+        key = rest;
+    }
+    let name = key.to_lowercase();
+    let code = match name.as_str() {
+        "esc" => KeyCode::Esc,
+        "enter" => KeyCode::Enter,
+        "insert" => KeyCode::Insert,
+        "delete" => KeyCode::Delete,
+        "space" => KeyCode::Char(' '),
+        "backspace" => KeyCode::Backspace,
+        "left" => KeyCode::Left,
+        "right" => KeyCode::Right,
+        "up" => KeyCode::Up,
+        "down" => KeyCode::Down,
+        "home" => KeyCode::Home,
+        "end" => KeyCode::End,
+        "pageup" => KeyCode::PageUp,
+        "pagedown" => KeyCode::PageDown,
+        "tab" if mods.contains(KeyModifiers::SHIFT) => {
+            mods.remove(KeyModifiers::SHIFT);
+            KeyCode::BackTab
+        }
+        "tab" => KeyCode::Tab,
+        "backtab" => KeyCode::BackTab,
+        _ => match name.strip_prefix('f').and_then(|n| n.parse::<u8>().ok()) {
+            Some(n) => KeyCode::F(n),
+            None => {
+                let mut c = key.chars();
+                match (c.next(), c.next()) {
+                    (Some(c), None)
+                        if mods.intersects(KeyModifiers::CONTROL | KeyModifiers::ALT) =>
+                    {
+                        KeyCode::Char(c.to_lowercase().next().unwrap_or(c))
+                    }
+                    (Some(c), None) => KeyCode::Char(c),
+                    _ => return None,
+                }
+            }
+        },
+    };
+    // shift is part of the character itself
+    if let KeyCode::Char(c) = code
+        && mods.contains(KeyModifiers::SHIFT)
+    {
+        mods.remove(KeyModifiers::SHIFT);
+        if !mods.is_empty() {
+            return None;
+        }
+        return Some((KeyCode::Char(c.to_uppercase().next().unwrap_or(c)), mods));
+    }
+    Some((code, mods))
+}
+
+/// Would the key type text into a test, being a character without ctrl or alt
+pub fn types_text(code: KeyCode, mods: KeyModifiers) -> bool {
+    matches!(code, KeyCode::Char(_)) && (mods - KeyModifiers::SHIFT).is_empty()
+}
+
+/// Is `key` one of the keys described by `spec`, separated by spaces and each as parsed by [`parse_key`].
+/// Modifiers must match exactly, except shift on characters, which is part of the character.
+/// Never for invalid or empty specs
+pub fn key_matches(spec: &str, key: &KeyEvent) -> bool {
+    let held = match key.code {
+        KeyCode::Char(_) | KeyCode::BackTab => key.modifiers - KeyModifiers::SHIFT,
+        _ => key.modifiers,
+    };
+    let code = match key.code {
+        // ctrl and alt characters are bound lowercase
+        KeyCode::Char(c) if held.intersects(KeyModifiers::CONTROL | KeyModifiers::ALT) => {
+            KeyCode::Char(c.to_lowercase().next().unwrap_or(c))
+        }
+        c => c,
+    };
+    spec.split_whitespace()
+        .filter_map(parse_key)
+        .any(|(c, mods)| c == code && mods == held)
+}
+
+/// Uppercase the first character of a string
+fn capitalize(s: &str) -> String {
+    let mut c = s.chars();
+    match c.next() {
+        Some(f) => f.to_uppercase().chain(c).collect(),
+        None => String::new(),
+    }
+}
+
+/// Marks injected around words by [`Punctuator`]
+const INJECTED: [char; 5] = ['.', ',', '"', '?', '!'];
+
+/// Keys pressed by the left hand when touch typing on QWERTY, other than punctuation
+const LEFT_HAND: &str = "12345qwertasdfgzxcvb";
+/// Keys pressed by the right hand when touch typing on QWERTY, other than punctuation
+const RIGHT_HAND: &str = "67890yuiophjklnm";
+
+/// Hand that typically presses a key
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Hand {
+    Left,
+    Right,
+}
+
+impl Hand {
+    /// Hand that presses `c` by the QWERTY hand map, None for keys not on it such as punctuation
+    fn of(c: char) -> Option<Self> {
+        let c = c.to_ascii_lowercase();
+        if LEFT_HAND.contains(c) {
+            Some(Self::Left)
+        } else if RIGHT_HAND.contains(c) {
+            Some(Self::Right)
+        } else {
+            None
+        }
+    }
+}
+
+/// Where the test sits when narrower than the screen
+#[derive(Deserialize, Serialize, Clone, Copy, Default, PartialEq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum TestAlignment {
+    #[default]
+    Left,
+    Center,
+}
+
+/// Synthesizes sentences over plain words, capitalizing their starts and adding punctuation
+struct Punctuator {
+    rng: StdRng,
+    /// The next word starts a sentence
+    sentence_start: bool,
+}
+
+impl Punctuator {
+    fn new(seed: u64) -> Self {
+        Self {
+            rng: StdRng::seed_from_u64(seed),
+            sentence_start: true,
+        }
+    }
+
+    /// The next word of the sentence
+    fn punctuate(&mut self, w: String) -> String {
+        let w = if self.sentence_start {
+            capitalize(&w)
+        } else {
+            w
+        };
+        self.sentence_start = false;
+        // percentages of each mark
+        match self.rng.random_range(0..100) {
+            0..10 => {
+                self.sentence_start = true;
+                format!("{w}.")
+            }
+            10..18 => format!("{w},"),
+            18..21 => format!("\"{w}\""),
+            21..24 => {
+                self.sentence_start = true;
+                format!("{w}{}", if self.rng.random_bool(0.5) { '?' } else { '!' })
+            }
+            _ => w,
+        }
+    }
+}
+
 /// A single keypress
 struct Keypress {
     key: char,
-    _time: Instant,
+    time: Instant,
+    /// Character matched the word where it was typed. Spaces and backspaces are correct unless rejected
+    correct: bool,
 }
 
 impl Keypress {
     /// Create keypress from char with current time as instant
-    fn from_chr(key: char) -> Self {
+    fn from_chr(key: char, correct: bool) -> Self {
         Self {
-            key: key,
-            _time: Instant::now(),
+            key,
+            time: Instant::now(),
+            correct,
         }
     }
 }
 
+/// One keypress of an exported keystroke log
+#[derive(Serialize)]
+struct LoggedKey {
+    /// Milliseconds since the first keypress of the test
+    ms: u64,
+    /// Character typed, or `space`, `backspace`, or `word_backspace`
+    key: String,
+    /// Character expected where it was typed, none for control keys or past the end of the word
+    expected: Option<char>,
+    /// `correct`, `incorrect`, or `control` for spaces and backspaces
+    kind: &'static str,
+    /// Index of the word it was typed in
+    word: usize,
+}
+
 /// A single test word and its keypresses.
 struct TestWord<'a> {
     word: String,
     presses: Vec<Keypress>,
     /// Renderable, incremental text "object"
     spans: Vec<Span<'a>>,
+    /// Current text as typed, kept in sync with `presses`
+    input: String,
+    /// Cached result of comparing `input` against `word`
+    correct: bool,
+    /// Skipped with the skip word key, leaving the rest of the word missed
+    skipped: bool,
 }
 
 impl From<String> for TestWord<'_> {
     fn from(string: String) -> Self {
         TestWord {
             presses: Vec::with_capacity(string.len()),
+            input: String::with_capacity(string.len()),
+            correct: string.is_empty(),
             word: string,
             spans: Vec::new(),
+            skipped: false,
         }
     }
 }
 
 impl TestWord<'_> {
-    /// Is the word fully and correctly typed
+    /// Record a keypress and incrementally update the typed and correct state.
+    /// Returns whether the keypress was correct
+    fn press(&mut self, key: char) -> bool {
+        let correct = match key {
+            ' ' | BKSPC | WORD_BKSPC => true,
+            _ => self.word.chars().nth(self.input.chars().count()) == Some(key),
+        };
+        self.presses.push(Keypress::from_chr(key, correct));
+        match key {
+            ' ' => return correct, // spaces never change the input
+            BKSPC => {
+                self.input.pop();
+            }
+            WORD_BKSPC => self.input.clear(),
+            _ => self.input.push(key),
+        }
+        self.correct = self.input == self.word;
+        correct
+    }
+
+    /// Record a keypress as an error without it changing the input, such as a doubled space
+    fn reject(&mut self, key: char) {
+        self.presses.push(Keypress::from_chr(key, false));
+    }
+
+    /// Is the word fully and correctly typed, recomputed from every keypress.
+    /// Used to verify the cached `correct` state.
+    #[cfg(test)]
     fn is_correct(&self) -> bool {
         let mut s: String = "".to_string();
         for e in self.presses.iter() {
@@ -64,18 +326,147 @@ impl TestWord<'_> {
                 _ => s.push(e.key),
             }
         }
-        return s == self.word;
+        s == self.word
+    }
+
+    /// Was the last keypress for this word a space that moved past it
+    fn is_spaced(&self) -> bool {
+        self.presses
+            .last()
+            .is_some_and(|lp| lp.key == ' ' && lp.correct)
     }
 
     /// Does the word end in a space (has been typed, incorrectly or correctly)
     fn is_typed(&self) -> bool {
-        if let Some(lp) = self.presses.last()
-            && lp.key == ' '
-        {
-            true
+        self.is_spaced() || self.correct
+    }
+
+    /// Every typed character in order, and whether it was later deleted
+    fn edit_history(&self) -> Vec<(char, bool)> {
+        let mut out: Vec<(char, bool)> = Vec::with_capacity(self.presses.len());
+        // indices into out of characters that have not been deleted
+        let mut live: Vec<usize> = Vec::with_capacity(self.word.len());
+        for e in self.presses.iter() {
+            match e.key {
+                ' ' => (),
+                BKSPC => {
+                    if let Some(i) = live.pop() {
+                        out[i].1 = true;
+                    }
+                }
+                WORD_BKSPC => {
+                    for i in live.drain(..) {
+                        out[i].1 = true;
+                    }
+                }
+                c => {
+                    live.push(out.len());
+                    out.push((c, false));
+                }
+            }
+        }
+        out
+    }
+
+    /// Incorrectly typed characters that were deleted again, and those left in the final input
+    fn error_fates(&self) -> (usize, usize) {
+        let mut corrected = 0;
+        // correctness of each character that has not been deleted
+        let mut live: Vec<bool> = Vec::with_capacity(self.word.len());
+        for e in self.presses.iter() {
+            match e.key {
+                ' ' => (),
+                BKSPC => {
+                    if live.pop() == Some(false) {
+                        corrected += 1;
+                    }
+                }
+                WORD_BKSPC => corrected += live.drain(..).filter(|c| !c).count(),
+                _ => live.push(e.correct),
+            }
+        }
+        (corrected, live.iter().filter(|c| !**c).count())
+    }
+}
+
+/// Raw counts of a test, for computing speed under the different definitions used by typing tests
+#[derive(Clone, Default)]
+pub struct TestMetrics {
+    /// Characters of correctly typed words, plus the spaces between them
+    pub correct_chars: usize,
+    /// Spaces counted in `correct_chars`
+    pub correct_spaces: usize,
+    /// Every character of the final input, right or wrong, plus the spaces between words
+    pub typed_chars: usize,
+    pub correct_words: usize,
+    /// Wrong, extra, and skipped over characters left in the final input
+    pub uncorrected_errors: usize,
+    /// Presses of characters, excluding spaces and backspaces
+    pub char_presses: usize,
+    /// Incorrect character presses, including corrected ones
+    pub errors: usize,
+    pub secs: f64,
+}
+
+/// Sum the counts of several tests, as if they were typed as one
+impl AddAssign for TestMetrics {
+    fn add_assign(&mut self, o: Self) {
+        self.correct_chars += o.correct_chars;
+        self.correct_spaces += o.correct_spaces;
+        self.typed_chars += o.typed_chars;
+        self.correct_words += o.correct_words;
+        self.uncorrected_errors += o.uncorrected_errors;
+        self.char_presses += o.char_presses;
+        self.errors += o.errors;
+        self.secs += o.secs;
+    }
+}
+
+impl TestMetrics {
+    fn minutes(&self) -> f64 {
+        self.secs / 60.0
+    }
+
+    /// Per minute rate of `n`, 0 if no time has passed
+    fn per_minute(&self, n: f64) -> f64 {
+        if self.secs <= 0.0 {
+            0.0
         } else {
-            self.is_correct()
+            n / self.minutes()
+        }
+    }
+
+    /// Correct characters including spaces over 5, as used by arstyper
+    pub fn net_wpm(&self) -> f64 {
+        self.per_minute(self.correct_chars as f64 / 5.0)
+    }
+
+    /// Correct characters excluding spaces over 5
+    pub fn no_space_wpm(&self) -> f64 {
+        self.per_minute((self.correct_chars - self.correct_spaces) as f64 / 5.0)
+    }
+
+    /// Every typed character over 5, ignoring mistakes
+    pub fn gross_wpm(&self) -> f64 {
+        self.per_minute(self.typed_chars as f64 / 5.0)
+    }
+
+    /// Gross WPM less uncorrected errors per minute, the ANSI standard net speed
+    pub fn ansi_wpm(&self) -> f64 {
+        (self.gross_wpm() - self.per_minute(self.uncorrected_errors as f64)).max(0.0)
+    }
+
+    /// Correctly typed words regardless of their length
+    pub fn literal_wpm(&self) -> f64 {
+        self.per_minute(self.correct_words as f64)
+    }
+
+    /// Percentage of character presses that were correct, 100 if there were none
+    pub fn accuracy(&self) -> f64 {
+        if self.char_presses == 0 {
+            return 100.0;
         }
+        self.char_presses.saturating_sub(self.errors) as f64 / self.char_presses as f64 * 100.0
     }
 }
 
@@ -87,168 +478,2255 @@ pub struct Test<'a> {
     /// Message to the UI to be performed on next tick. Didn't feel like using an actual message system lmao
     tx: SyncSender<UiRequest>,
     title: String,
+    cfg: TestCfg,
+    /// Test has been finished and no longer accepts input
+    completed: bool,
+    /// When the test was paused, if it is
+    paused_at: Option<Instant>,
+    /// Total time spent paused after the first keypress, excluded from elapsed time
+    paused_for: Duration,
+    /// Style incorrect characters as correct ones
+    quiet_errors: bool,
+    /// Style correct characters as typed, otherwise as untyped so only errors stand out
+    highlight_correct: bool,
+    /// Test was ended early for falling below the minimum live accuracy
+    aborted: bool,
+    /// Show this many lines at a time, advancing a page at a time, 0 to wrap all words
+    lines_per_page: usize,
+    /// Show a bar of current keystrokes per second in the footer
+    show_kps: bool,
+    /// Shade every other chunk of this many untyped letters, 0 to disable
+    chunk_size: usize,
+    /// Keys that restart the test and edit it while typing
+    keys: KeybindsCfg,
+    /// Widest the test is drawn, 0 for the full width
+    max_width: u16,
+    /// Where the test sits in areas wider than `max_width`
+    alignment: TestAlignment,
+    /// Words carry their own case and punctuation, so are tested verbatim
+    punctuated: bool,
+    /// Leading words that are typed but not scored
+    warmup: usize,
+    /// Latest mistyped characters, oldest first, as the expected character (none past the end of a word) and the typed one
+    recent_errors: VecDeque<(Option<char>, char)>,
+    /// A case-only mistake has already prompted a Caps Lock warning this test
+    caps_warned: bool,
+    /// Test never finishes, words are appended with [`Test::extend`] as they run low
+    endless: bool,
+    /// When the words were set, from which the reveal before obscuring is counted
+    set_at: Instant,
+    /// Seed of the punctuation injected into following tests
+    seed: u64,
+    /// Punctuation injected into the current test, continued by [`Test::extend`]
+    punctuator: Punctuator,
+}
+
+/// Builder for a configured [`Test`]
+pub struct TestBuilder {
+    styles: Styles,
+    tx: SyncSender<UiRequest>,
+    cfg: TestCfg,
+    quiet_errors: bool,
+    highlight_correct: bool,
+    lines_per_page: usize,
+    show_kps: bool,
+    chunk_size: usize,
+    keys: KeybindsCfg,
+    max_width: u16,
+    alignment: TestAlignment,
+    punctuated: bool,
+    endless: bool,
+}
+
+impl TestBuilder {
+    pub fn new(styles: Styles, tx: SyncSender<UiRequest>) -> Self {
+        Self {
+            styles,
+            tx,
+            cfg: TestCfg::default(),
+            quiet_errors: false,
+            highlight_correct: true,
+            lines_per_page: 0,
+            show_kps: false,
+            chunk_size: 0,
+            keys: KeybindsCfg::default(),
+            max_width: 0,
+            alignment: TestAlignment::default(),
+            punctuated: false,
+            endless: false,
+        }
+    }
+
+    /// Use all behaviour from config, replacing any set before
+    pub fn cfg(mut self, cfg: TestCfg) -> Self {
+        self.cfg = cfg;
+        self
+    }
+
+    /// Keep the case of words, such as for quotations
+    pub fn punctuated(mut self, b: bool) -> Self {
+        self.punctuated = b;
+        self
+    }
+
+    /// Never finish or end early, taking more words as they are typed
+    pub fn endless(mut self, b: bool) -> Self {
+        self.endless = b;
+        self
+    }
+
+    /// Style incorrect characters as correct ones, only counting errors
+    pub fn quiet_errors(mut self, b: bool) -> Self {
+        self.quiet_errors = b;
+        self
+    }
+
+    /// Style correct characters as typed, otherwise they look untyped
+    pub fn highlight_correct(mut self, b: bool) -> Self {
+        self.highlight_correct = b;
+        self
+    }
+
+    /// Lay words out in pages of `n` lines instead of wrapping them all, 0 to disable
+    pub fn lines_per_page(mut self, n: usize) -> Self {
+        self.lines_per_page = n;
+        self
+    }
+
+    /// Show current keystrokes per second while testing
+    pub fn show_kps(mut self, b: bool) -> Self {
+        self.show_kps = b;
+        self
+    }
+
+    /// Shade every other chunk of `n` letters in untyped words, 0 to disable
+    pub fn chunk_size(mut self, n: usize) -> Self {
+        self.chunk_size = n;
+        self
+    }
+
+    /// Keys to restart the test and edit it while typing, as matched by [`key_matches`]
+    pub fn keybinds(mut self, keys: KeybindsCfg) -> Self {
+        self.keys = keys;
+        self
+    }
+
+    /// Draw the test at most `width` columns wide, aligned within wider areas. 0 for the full width
+    pub fn max_width(mut self, width: u16, alignment: TestAlignment) -> Self {
+        self.max_width = width;
+        self.alignment = alignment;
+        self
+    }
+
+    /// Create the test, which must still be given words with [`Test::test_from`]
+    pub fn build<'a>(self) -> Test<'a> {
+        #[allow(deprecated)]
+        let mut t = Test::new(self.styles, self.tx);
+        t.set_cfg(self.cfg);
+        t.quiet_errors = self.quiet_errors;
+        t.highlight_correct = self.highlight_correct;
+        t.lines_per_page = self.lines_per_page;
+        t.show_kps = self.show_kps;
+        t.chunk_size = self.chunk_size;
+        t.keys = self.keys;
+        t.max_width = self.max_width;
+        t.alignment = self.alignment;
+        t.set_punctuated(self.punctuated);
+        t.set_endless(self.endless);
+        t
+    }
 }
 
 impl<'a> Test<'a> {
     /// Create a new emtpy test, which must be initialised before use :D
+    #[deprecated(note = "use `TestBuilder` instead")]
     pub fn new(s: Styles, tx: SyncSender<UiRequest>) -> Self {
         Test {
             words: Vec::new(),
             word_i: 0,
             styles: s,
-            tx: tx,
+            tx,
             title: "".to_string(),
+            cfg: TestCfg::default(),
+            completed: false,
+            paused_at: None,
+            paused_for: Duration::ZERO,
+            quiet_errors: false,
+            highlight_correct: true,
+            aborted: false,
+            lines_per_page: 0,
+            show_kps: false,
+            chunk_size: 0,
+            keys: KeybindsCfg::default(),
+            max_width: 0,
+            alignment: TestAlignment::default(),
+            punctuated: false,
+            warmup: 0,
+            recent_errors: VecDeque::with_capacity(RECENT_ERRORS),
+            caps_warned: false,
+            endless: false,
+            set_at: Instant::now(),
+            seed: 0,
+            punctuator: Punctuator::new(0),
         }
     }
 
-    /// Set title
-    pub fn set_title(&mut self, title: String) {
-        self.title = title;
+    /// Has the test been finished
+    pub fn is_completed(&self) -> bool {
+        self.completed
     }
 
-    /// Handle keypress events for this test
-    pub fn handle_events(&mut self, key: KeyEvent) {
-        let mut word = &mut self.words[self.word_i];
-        match key.code {
-            KeyCode::Char(' ') => {
-                word.presses.push(Keypress::from_chr(' '));
-                self.word_i += 1;
-            }
-            KeyCode::Char(chr) => {
-                word.presses.push(Keypress::from_chr(chr));
+    /// Target text of each word in the test, as it is to be typed
+    pub fn words(&self) -> impl Iterator<Item = &str> {
+        self.words.iter().map(|tw| tw.word.as_str())
+    }
 
-                let len = word.spans.len();
-                // potential correct press
-                if len < word.word.len() && chr == word.word.chars().nth(len).unwrap() {
-                    word.spans
-                        .push(Span::raw(chr.to_string()).style(self.styles.typed));
-                }
-                // incorrect press
-                else {
-                    word.spans
-                        .push(Span::raw(chr.to_string()).style(self.styles.incorrect));
+    /// Incorrect character presses so far, including corrected ones
+    pub fn errors(&self) -> usize {
+        self.scored()
+            .iter()
+            .flat_map(|tw| tw.presses.iter())
+            .filter(|kp| !kp.correct)
+            .count()
+    }
+
+    /// Incorrect characters that were fixed with backspace, and those left in place
+    pub fn error_fates(&self) -> (usize, usize) {
+        self.scored()
+            .iter()
+            .map(|tw| tw.error_fates())
+            .fold((0, 0), |(c, u), (wc, wu)| (c + wc, u + wu))
+    }
+
+    /// Was the test ended early for low accuracy
+    pub fn is_aborted(&self) -> bool {
+        self.aborted
+    }
+
+    /// Presses of characters, excluding spaces and backspaces unless they were errors
+    fn char_presses(&self) -> usize {
+        self.scored()
+            .iter()
+            .flat_map(|tw| tw.presses.iter())
+            .filter(|kp| !kp.correct || ![' ', BKSPC, WORD_BKSPC].contains(&kp.key))
+            .count()
+    }
+
+    /// Percentage of character presses that were correct, ignoring spaces and backspaces.
+    /// A test without any character presses is 100% accurate, as nothing was mistyped
+    pub fn accuracy(&self) -> f64 {
+        self.metrics().accuracy()
+    }
+
+    /// Live accuracy has fallen below the configured floor, after enough presses to be meaningful
+    fn below_min_accuracy(&self) -> bool {
+        if self.cfg.min_live_accuracy <= 0.0 || self.errors() == 0 {
+            return false;
+        }
+        self.char_presses() >= MIN_ACCURACY_PRESSES && self.accuracy() < self.cfg.min_live_accuracy
+    }
+
+    /// Words counted towards results, after any warmup words
+    fn scored(&self) -> &[TestWord<'a>] {
+        &self.words[min(self.warmup, self.words.len())..]
+    }
+
+    /// Latest mistyped characters, oldest first, as the expected character and the typed one.
+    /// The expected character is none for characters typed past the end of a word
+    pub fn recent_errors(&self) -> impl Iterator<Item = &(Option<char>, char)> {
+        self.recent_errors.iter()
+    }
+
+    /// Scored words moved past so far and the total, such as (12, 50)
+    pub fn progress(&self) -> (usize, usize) {
+        let done = self.word_i.saturating_sub(self.warmup);
+        let total = self.word_count();
+        (
+            if self.completed {
+                total
+            } else {
+                done.min(total)
+            },
+            total,
+        )
+    }
+
+    /// Number of scored words in the test
+    pub fn word_count(&self) -> usize {
+        self.scored().len()
+    }
+
+    /// Scored words from the one in progress on, as given before any reversing
+    pub fn remaining_words(&self) -> Vec<String> {
+        self.words[self.word_i.max(self.warmup).min(self.words.len())..]
+            .iter()
+            .map(|tw| {
+                if self.cfg.reverse {
+                    tw.word.chars().rev().collect()
+                } else {
+                    tw.word.to_string()
                 }
-            }
-            KeyCode::Tab => self
-                .tx
-                .send(UiRequest::ChangeScreen(Screen::ResultsScreen))
-                .unwrap(),
-            KeyCode::Backspace => {
-                // (ctrl|alt) + backspace -> delete entire word
-                if key
-                    .modifiers
+            })
+            .collect()
+    }
+
+    /// Keys typed of the word in progress, to pick it up again with [`Test::replay_word`].
+    /// Rejected spaces are left out as they never changed the word
+    pub fn partial_keys(&self) -> Vec<char> {
+        self.words
+            .get(self.word_i)
+            .map(|tw| {
+                tw.presses
                     .iter()
-                    .any(|m| m == KeyModifiers::CONTROL || m == KeyModifiers::ALT)
-                {
-                    // delete last word cause nothing was typed for this one
-                    if word.spans.len() == 0 {
-                        self.word_i -= 1;
-                        word = &mut self.words[self.word_i];
-                    }
+                    .map(|kp| kp.key)
+                    .filter(|&k| k != ' ')
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
 
-                    word.presses.push(Keypress::from_chr(WORD_BKSPC));
-                    word.spans = Vec::new();
-                }
-                // just backspace
-                else {
-                    word.presses.push(Keypress::from_chr(BKSPC));
-                    let _ = word.spans.pop();
-                    if self.word_i > 0 && word.spans.len() == 0 {
-                        self.word_i -= 1;
-                    }
+    /// Type `keys` of [`Test::partial_keys`] into the current word and pause until the next keypress.
+    /// False if they aren't keys of a single word, leaving it untyped
+    pub fn replay_word(&mut self, keys: &[char]) -> bool {
+        let valid =
+            |&k: &char| k == BKSPC || k == WORD_BKSPC || !(k.is_whitespace() || k.is_control());
+        if self.word_i >= self.words.len() || !keys.iter().all(valid) {
+            return false;
+        }
+        let correct_sty = if self.highlight_correct {
+            self.styles.typed
+        } else {
+            self.styles.untyped
+        };
+        let incorrect_sty = if self.quiet_errors {
+            correct_sty
+        } else {
+            self.styles.incorrect
+        };
+        let word = &mut self.words[self.word_i];
+        for &k in keys {
+            let correct = word.press(k);
+            match k {
+                BKSPC => {
+                    word.spans.pop();
                 }
+                WORD_BKSPC => word.spans.clear(),
+                _ => word.spans.push(Span::raw(k.to_string()).style(if correct {
+                    correct_sty
+                } else {
+                    incorrect_sty
+                })),
             }
-            _ => {}
         }
-        // check for completion
-        if self.word_i >= self.words.len() - 1 && self.words[self.words.len() - 1].is_typed() {
-            self.tx
-                .send(UiRequest::ChangeScreen(Screen::ResultsScreen))
-                .unwrap();
+        self.pause();
+        true
+    }
+
+    /// Words keep their case
+    pub fn is_punctuated(&self) -> bool {
+        self.punctuated || self.injects()
+    }
+
+    /// Punctuation and capitals are injected into the words, which aren't already punctuated
+    fn injects(&self) -> bool {
+        self.cfg.inject_punctuation && !self.punctuated
+    }
+
+    /// Test never finishes
+    pub fn is_endless(&self) -> bool {
+        self.endless
+    }
+
+    /// Words left to type after the one in progress
+    pub fn words_left(&self) -> usize {
+        self.words.len().saturating_sub(self.word_i + 1)
+    }
+
+    /// Correctly typed characters, counting the space after each correct word except the last
+    pub fn correct_chars(&self) -> usize {
+        let n: usize = self
+            .scored()
+            .iter()
+            .filter(|tw| tw.correct)
+            .map(|tw| tw.word.chars().count() + 1)
+            .sum();
+        n.saturating_sub(1)
+    }
+
+    /// Each typed word and whether it was typed correctly, without any injected punctuation
+    pub fn word_results(&self) -> impl Iterator<Item = (&str, bool)> {
+        let injected = self.injects();
+        self.scored()
+            .iter()
+            .filter(|tw| tw.is_typed())
+            .map(move |tw| {
+                let w = if injected {
+                    tw.word.trim_matches(INJECTED)
+                } else {
+                    tw.word.as_str()
+                };
+                (w, tw.correct)
+            })
+    }
+
+    /// Counts for computing speed under different definitions
+    pub fn metrics(&self) -> TestMetrics {
+        let typed = self
+            .scored()
+            .iter()
+            .filter(|tw| !tw.presses.is_empty())
+            .collect::<Vec<&TestWord>>();
+        let correct_words = self.scored().iter().filter(|tw| tw.correct).count();
+        let uncorrected_errors = typed
+            .iter()
+            .map(|tw| {
+                let (input, word) = (tw.input.chars().count(), tw.word.chars().count());
+                let wrong = tw
+                    .input
+                    .chars()
+                    .zip(tw.word.chars())
+                    .filter(|(a, b)| a != b)
+                    .count();
+                let missed = if tw.is_spaced() {
+                    word.saturating_sub(input)
+                } else {
+                    0
+                };
+                wrong + input.saturating_sub(word) + missed
+            })
+            .sum();
+        TestMetrics {
+            correct_chars: self.correct_chars(),
+            correct_spaces: correct_words.saturating_sub(1),
+            typed_chars: typed
+                .iter()
+                .map(|tw| tw.input.chars().count())
+                .sum::<usize>()
+                + typed.len().saturating_sub(1),
+            correct_words,
+            uncorrected_errors,
+            char_presses: self.char_presses(),
+            errors: self.errors(),
+            secs: self.elapsed_secs(),
+        }
+    }
+
+    /// Seconds from the first to the last keypress of the test
+    pub fn elapsed_secs(&self) -> f64 {
+        let mut times = self
+            .scored()
+            .iter()
+            .flat_map(|tw| tw.presses.iter())
+            .map(|kp| kp.time);
+        match (times.next(), times.next_back()) {
+            (Some(first), Some(last)) => last
+                .duration_since(first)
+                .saturating_sub(self.paused_for)
+                .as_secs_f64(),
+            _ => 0.0,
         }
     }
 
-    /// Return full word as vec of spans, including untyped portion
-    fn tw_as_span_vec(&self, word_i: usize, tw: &TestWord<'a>) -> Vec<Span<'a>> {
-        // typed portion
-        let mut sv = tw.spans.clone();
+    /// Net speed of the test, correct characters over 5 per minute. 0 before any time has passed
+    pub fn wpm(&self) -> f64 {
+        self.metrics().net_wpm()
+    }
 
-        // cursor
-        if self.word_i == word_i {
-            match tw.word.chars().nth(sv.len()) {
-                Some(c) => sv.push(Span::raw(c.to_string()).style(self.styles.cursor)),
-                None => {
-                    // must be end of string, add stylized space and return.
-                    sv.push(Span::raw(' '.to_string()).style(self.styles.cursor));
-                    return sv;
-                }
+    /// Speed of each correctly typed word in WPM, from the space before it to its last character.
+    /// The first word is timed from its first character instead.
+    pub fn word_wpm(&self) -> Vec<f64> {
+        let mut out = Vec::with_capacity(self.scored().len());
+        for (i, tw) in self.scored().iter().enumerate() {
+            let Some(end) = tw.presses.iter().rev().find(|kp| kp.key != ' ') else {
+                continue;
             };
+            if !tw.correct {
+                continue;
+            }
+            let prev = i
+                .checked_sub(1)
+                .and_then(|j| self.scored()[j].presses.last());
+            let (start, chars) = match prev {
+                Some(kp) => (kp.time, tw.word.chars().count()),
+                None => (tw.presses[0].time, tw.word.chars().count() - 1),
+            };
+            let secs = end.time.duration_since(start).as_secs_f64();
+            if secs > 0.0 {
+                out.push(SpeedUnit::Wpm.speed(chars, secs));
+            }
+        }
+        out
+    }
+
+    /// Speed through the test as (seconds, WPM) at the end of each `bucket` of time, from the
+    /// correct characters typed within it. Any time left over is merged into the last bucket, so it
+    /// can't spike from a few characters in a sliver of time, and tests shorter than one bucket
+    /// still get a point. Empty before any time has passed
+    pub fn wpm_over_time(&self, bucket: Duration) -> Vec<(f64, f64)> {
+        let presses = self
+            .scored()
+            .iter()
+            .flat_map(|tw| tw.presses.iter())
+            .collect::<Vec<&Keypress>>();
+        let (Some(first), Some(last)) = (presses.first(), presses.last()) else {
+            return Vec::new();
+        };
+        let total = last.time.duration_since(first.time).as_secs_f64();
+        let b = bucket.as_secs_f64();
+        if total <= 0.0 || b <= 0.0 {
+            return Vec::new();
         }
 
-        // untyped portion
-        let idx = min(sv.len(), tw.word.len());
-        let ut = tw.word[idx..].to_string() + " ";
-        sv.push(Span::raw(ut).style(self.styles.untyped));
-        return sv;
+        let n = ((total / b) as usize).max(1);
+        let mut counts = vec![0; n];
+        for kp in presses.iter().skip(1) {
+            if kp.correct && kp.key != BKSPC && kp.key != WORD_BKSPC {
+                let i = (kp.time.duration_since(first.time).as_secs_f64() / b) as usize;
+                counts[i.min(n - 1)] += 1;
+            }
+        }
+        let len = counts.len();
+        counts
+            .into_iter()
+            .enumerate()
+            .map(|(i, n)| {
+                let end = if i + 1 == len {
+                    total
+                } else {
+                    b * (i + 1) as f64
+                };
+                (end, SpeedUnit::Wpm.speed(n, end - b * i as f64))
+            })
+            .collect()
     }
 
-    /// Create test from an iterator over string items
-    pub fn test_from(&mut self, words: impl Iterator<Item = String>) {
-        self.words = words
-            .map(|w| w.to_lowercase().into())
-            .collect::<Vec<TestWord>>();
+    /// Percentage of keypresses that came less than `threshold` after the previous one,
+    /// approximating overlapping keys. None with fewer than two presses.
+    pub fn burst_percent(&self, threshold: Duration) -> Option<f64> {
+        let times = self
+            .scored()
+            .iter()
+            .flat_map(|tw| tw.presses.iter())
+            .map(|kp| kp.time)
+            .collect::<Vec<Instant>>();
+        let intervals = times.windows(2).map(|w| w[1].duration_since(w[0]));
+        let bursts = intervals.clone().filter(|d| *d < threshold).count();
+        let n = intervals.count();
+        (n > 0).then(|| bursts as f64 / n as f64 * 100.0)
     }
 
-    /// Render the test text
-    pub fn render(&self, area: Rect, buf: &mut Buffer) {
-        Paragraph::new(self.words_to_line())
-            .style(self.styles.root)
-            .block(
-                Block::new()
-                    .borders(Borders::TOP)
-                    .style(self.styles.accent)
-                    .title(self.title.clone().bold()) // TODO this is annoying and bad
-                    .padding(Padding::horizontal(1)),
-            )
-            .wrap(Wrap { trim: true })
-            .render(area, buf);
+    /// Average milliseconds from the previous keypress to each correctly typed key of `hand`.
+    /// None if it typed no keys after another
+    pub fn hand_interval(&self, hand: Hand) -> Option<f64> {
+        let presses = self
+            .scored()
+            .iter()
+            .flat_map(|tw| tw.presses.iter())
+            .collect::<Vec<&Keypress>>();
+        let intervals = presses
+            .windows(2)
+            .filter(|w| w[1].correct && Hand::of(w[1].key) == Some(hand))
+            .map(|w| w[1].time.duration_since(w[0].time).as_secs_f64() * 1000.0)
+            .collect::<Vec<f64>>();
+        (!intervals.is_empty()).then(|| intervals.iter().sum::<f64>() / intervals.len() as f64)
     }
 
-    /// Convert all testwords to styled spans with spacing, returned as a single line so that it wraps properly
-    pub fn words_to_line(&self) -> Line<'a> {
-        Line::from(
-            self.words
-                .iter()
-                .enumerate()
-                .map(|(i, tw)| self.tw_as_span_vec(i, tw))
-                .flatten()
-                .collect::<Vec<Span>>(),
-        )
+    /// Keystrokes per second over the last few seconds
+    fn kps(&self) -> f64 {
+        let n = self
+            .scored()
+            .iter()
+            .flat_map(|tw| tw.presses.iter())
+            .filter(|kp| kp.time.elapsed() < KPS_WINDOW)
+            .count();
+        n as f64 / KPS_WINDOW.as_secs_f64()
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    /// Has any key been pressed yet
+    pub fn is_started(&self) -> bool {
+        self.words.iter().any(|tw| !tw.presses.is_empty())
+    }
 
-    #[test]
-    fn test_keypress_correct() {
-        let tests: Vec<(&str, Vec<char>, bool)> = vec![
-            ("test", vec!['t', 'e', 's', 't'], true),
-            ("test", vec![' ', 'q', BKSPC, 't', 'e', 's', 't', ' '], true),
-            (
-                "test",
-                vec![
-                    't', 'e', 's', 't', 'a', 'b', 'c', WORD_BKSPC, 't', 'e', 's', 't', ' ',
-                ],
-                true,
-            ),
-            ("abcd", vec!['a', 'b', 'c', 'd', 'e'], false),
+    /// Every keypress of the test as one JSON object per line, for analysis with other tools
+    pub fn keystroke_log(&self) -> String {
+        let Some(start) = self.started_at() else {
+            return String::new();
+        };
+        let mut s = String::new();
+        for (i, tw) in self.words.iter().enumerate() {
+            // replay the input length to find the character each press was meant to be
+            let mut pos: usize = 0;
+            for kp in tw.presses.iter() {
+                let (key, expected) = match kp.key {
+                    ' ' => ("space".to_string(), None),
+                    BKSPC => {
+                        pos = pos.saturating_sub(1);
+                        ("backspace".to_string(), None)
+                    }
+                    WORD_BKSPC => {
+                        pos = 0;
+                        ("word_backspace".to_string(), None)
+                    }
+                    c => {
+                        pos += 1;
+                        (c.to_string(), tw.word.chars().nth(pos - 1))
+                    }
+                };
+                let kind = match (kp.key, kp.correct) {
+                    (_, false) => "incorrect",
+                    (' ' | BKSPC | WORD_BKSPC, _) => "control",
+                    _ => "correct",
+                };
+                let k = LoggedKey {
+                    ms: kp.time.duration_since(start).as_millis() as u64,
+                    key,
+                    expected,
+                    kind,
+                    word: i,
+                };
+                s.push_str(&serde_json::to_string(&k).unwrap());
+                s.push('\n');
+            }
+        }
+        s
+    }
+
+    /// Time of the first keypress, None before the test has started
+    pub fn started_at(&self) -> Option<Instant> {
+        self.words
+            .iter()
+            .flat_map(|tw| tw.presses.iter())
+            .map(|kp| kp.time)
+            .next()
+    }
+
+    /// Time left showing the words before they are obscured, None once they are or if they never will be.
+    /// The reveal ends early when typing starts
+    pub fn reveal_left(&self) -> Option<Duration> {
+        if !self.cfg.obscure || self.is_started() {
+            return None;
+        }
+        Duration::from_secs(self.cfg.obscure_reveal_secs)
+            .checked_sub(self.set_at.elapsed())
+            .filter(|d| !d.is_zero())
+    }
+
+    /// Untyped characters are hidden
+    fn is_obscured(&self) -> bool {
+        self.cfg.obscure && self.reveal_left().is_none()
+    }
+
+    /// Pause the test clock until resumed
+    pub fn pause(&mut self) {
+        if self.paused_at.is_none() && !self.completed {
+            self.paused_at = Some(Instant::now());
+        }
+    }
+
+    /// Resume a paused test, not counting the pause toward elapsed time
+    pub fn resume(&mut self) {
+        if let Some(t) = self.paused_at.take()
+            && self.is_started()
+        {
+            self.paused_for += t.elapsed();
+        }
+    }
+
+    /// Set test behaviour
+    pub fn set_cfg(&mut self, cfg: TestCfg) {
+        self.cfg = cfg;
+    }
+
+    /// Set text styles, such as after the caret style changes
+    pub fn set_styles(&mut self, styles: Styles) {
+        self.styles = styles;
+    }
+
+    /// Keep the case of the following tests' words, such as for quotations
+    pub fn set_punctuated(&mut self, b: bool) {
+        self.punctuated = b;
+    }
+
+    /// Seed the punctuation injected into following tests, so the same seed injects the same
+    pub fn set_seed(&mut self, seed: u64) {
+        self.seed = seed;
+    }
+
+    /// Make the test endless, never finishing or ending early
+    pub fn set_endless(&mut self, b: bool) {
+        self.endless = b;
+    }
+
+    /// Set title
+    pub fn set_title(&mut self, title: String) {
+        self.title = title;
+    }
+
+    /// Handle keypress events for this test
+    pub fn handle_events(&mut self, key: KeyEvent) {
+        // restart with fresh words, even when frozen on completion
+        if key_matches(&self.keys.restart, &key) {
+            self.restart();
+            return;
+        }
+        // frozen on completion, any key proceeds to results
+        if self.completed {
+            self.show_results();
+            return;
+        }
+        // ended early and already on the way to results
+        if self.aborted {
+            return;
+        }
+        // nothing to type, such as an empty test or an endless one waiting on more words
+        if self.word_i >= self.words.len() {
+            return;
+        }
+        self.resume();
+
+        let started = self.is_started();
+        let skip = key_matches(&self.cfg.skip_word_key, &key);
+        let next_word = key_matches(&self.keys.next_word, &key);
+        let end_test = !self.endless && key_matches(&self.keys.end_test, &key);
+        // delete can stand in for backspace, with the same modifiers
+        let as_backspace = match key.code {
+            KeyCode::Delete if self.cfg.delete_as_backspace => {
+                KeyEvent::new(KeyCode::Backspace, key.modifiers)
+            }
+            _ => key,
+        };
+        let delete_word = key_matches(&self.keys.delete_word, &as_backspace);
+        let backspace = key_matches(&self.keys.backspace, &as_backspace);
+        let correct_sty = if self.highlight_correct {
+            self.styles.typed
+        } else {
+            self.styles.untyped
+        };
+        let step_back = self.can_step_back();
+        let mut word = &mut self.words[self.word_i];
+        match key.code {
+            _ if skip => {
+                word.skipped = true;
+                word.press(' ');
+                self.word_i += 1;
+            }
+            // a space before any input would skip the first word
+            _ if next_word && !started && !self.cfg.count_leading_space => {}
+            // a space with nothing typed yet would skip the word
+            _ if next_word && self.cfg.strict_spaces && started && word.spans.is_empty() => {
+                word.reject(' ');
+            }
+            _ if next_word => {
+                word.press(' ');
+                self.word_i += 1;
+            }
+            _ if end_test => self.show_results(),
+            _ if delete_word => {
+                // delete last word cause nothing was typed for this one
+                if word.spans.is_empty() && step_back {
+                    self.word_i -= 1;
+                    word = &mut self.words[self.word_i];
+                }
+
+                word.press(WORD_BKSPC);
+                word.spans = Vec::new();
+            }
+            _ if backspace => {
+                word.press(BKSPC);
+                let _ = word.spans.pop();
+                if word.spans.is_empty() && step_back {
+                    self.word_i -= 1;
+                }
+            }
+            // leniency for stutters past the end of a word
+            KeyCode::Char(_)
+                if self.cfg.ignore_overflow && word.spans.len() >= word.word.chars().count() => {}
+            KeyCode::Char(chr) => {
+                if word.press(chr) {
+                    word.spans
+                        .push(Span::raw(chr.to_string()).style(correct_sty));
+                }
+                // incorrect press
+                else {
+                    if self.recent_errors.len() == RECENT_ERRORS {
+                        self.recent_errors.pop_front();
+                    }
+                    let expected = word.word.chars().nth(word.spans.len());
+                    self.recent_errors.push_back((expected, chr));
+                    // wrong only by case, once per test
+                    if self.cfg.caps_lock_warning
+                        && !self.caps_warned
+                        && expected
+                            .is_some_and(|e| e != chr && e.to_lowercase().eq(chr.to_lowercase()))
+                    {
+                        self.caps_warned = true;
+                        let _ = self.tx.try_send(UiRequest::DisplayStatus(
+                            Priority::Warn,
+                            "Check Caps Lock?".to_string(),
+                            TimeDelta::seconds(3),
+                        ));
+                    }
+                    let sty = if self.quiet_errors {
+                        correct_sty
+                    } else {
+                        self.styles.incorrect
+                    };
+                    word.spans.push(Span::raw(chr.to_string()).style(sty));
+                }
+            }
+            _ => {}
+        }
+        if self.endless {
+            return;
+        }
+        if self.below_min_accuracy() {
+            self.aborted = true;
+            let _ = self.tx.try_send(UiRequest::DisplayStatus(
+                Priority::Warn,
+                format!(
+                    "Accuracy fell below {}%, test ended.",
+                    self.cfg.min_live_accuracy
+                ),
+                TimeDelta::seconds(3),
+            ));
+            self.show_results();
+        } else if self.is_finished() {
+            self.completed = true;
+            if !self.cfg.freeze_on_complete {
+                self.show_results();
+            }
+        }
+    }
+
+    /// Can backspace move back into the previous word. Completed words may be locked, and correct ones are unless allowed
+    fn can_step_back(&self) -> bool {
+        self.word_i > 0
+            && !self.cfg.lock_completed_words
+            && (self.cfg.allow_backspace_correct || !self.words[self.word_i - 1].correct)
+    }
+
+    /// Check for completion, which is when the last word has been typed.
+    /// A single word test always requires the trailing space, so it isn't over on the first correct keypress.
+    /// An empty test can never be finished.
+    fn is_finished(&self) -> bool {
+        let Some(last) = self.words.last() else {
+            return false;
+        };
+        if self.word_i < self.words.len().saturating_sub(1) {
+            return false;
+        }
+        if self.words.len() == 1 {
+            last.is_spaced()
+        } else {
+            last.is_typed()
+        }
+    }
+
+    /// Ask the UI to discard this test for a new one
+    fn restart(&self) {
+        // the UI reads requests on the thread sending them, so blocking on a full channel would never return
+        let _ = self.tx.try_send(UiRequest::NewTest);
+    }
+
+    /// Ask the UI to move on to the results screen
+    fn show_results(&self) {
+        let _ = self.tx.try_send(UiRequest::ChangeScreen(Screen::Results));
+    }
+
+    /// Return full word as vec of spans, including untyped portion
+    fn tw_as_span_vec(&self, word_i: usize, tw: &TestWord<'a>) -> Vec<Span<'a>> {
+        // typed portion, dimmed for unscored warmup words
+        let mut sv = tw.spans.clone();
+        let warmup = word_i < self.warmup;
+        if warmup {
+            for s in sv.iter_mut() {
+                s.style = s.style.add_modifier(Modifier::DIM);
+            }
+        }
+
+        // cursor
+        let obscured = self.is_obscured();
+        if self.word_i == word_i && !self.completed {
+            match tw.word.chars().nth(sv.len()) {
+                Some(c) => {
+                    let c = if obscured { OBSCURED } else { c };
+                    sv.push(Span::raw(c.to_string()).style(self.styles.cursor))
+                }
+                None => {
+                    // must be end of string, add stylized space and return.
+                    sv.push(Span::raw(' '.to_string()).style(self.styles.cursor));
+                    return sv;
+                }
+            };
+        }
+
+        // untyped portion, by chars as wide glyphs are multi-byte. Chunks are counted from the
+        // start of the word so they don't shift while typing
+        let typed = sv.len();
+        let untyped = tw
+            .word
+            .chars()
+            .enumerate()
+            .skip(typed)
+            .map(|(i, c)| (i, if obscured { OBSCURED } else { c }))
+            .map(|(i, c)| match self.chunk_size {
+                n if n > 0 && (i / n) % 2 == 1 => (c, self.styles.chunk),
+                _ => (c, self.styles.untyped),
+            });
+        for (c, sty) in untyped.chain([(' ', self.styles.untyped)]) {
+            let sty = if warmup {
+                sty.add_modifier(Modifier::DIM)
+            } else {
+                sty
+            };
+            let merge = sv.len() > typed;
+            match sv.last_mut() {
+                Some(s) if merge && s.style == sty => s.content.to_mut().push(c),
+                _ => sv.push(Span::raw(c.to_string()).style(sty)),
+            }
+        }
+        sv
+    }
+
+    /// Review of the whole test as typed, for the results screen.
+    /// With `show_deleted`, characters that were typed and then deleted are shown crossed out.
+    pub fn review_line(&self, show_deleted: bool) -> Line<'a> {
+        let mut sv: Vec<Span<'a>> = Vec::new();
+        for tw in self.words.iter() {
+            let target = tw.word.chars().collect::<Vec<char>>();
+            let mut pos = 0;
+            for (c, deleted) in tw.edit_history() {
+                if deleted {
+                    if show_deleted {
+                        sv.push(
+                            Span::raw(c.to_string())
+                                .style(self.styles.incorrect.add_modifier(Modifier::CROSSED_OUT)),
+                        );
+                    }
+                    continue;
+                }
+
+                let sty = if target.get(pos) == Some(&c) {
+                    self.styles.typed
+                } else {
+                    self.styles.incorrect
+                };
+                sv.push(Span::raw(c.to_string()).style(sty));
+                pos += 1;
+            }
+
+            // missed portion
+            let missed = target.iter().skip(pos).collect::<String>();
+            if tw.skipped {
+                sv.push(
+                    Span::raw(missed)
+                        .style(self.styles.incorrect.add_modifier(Modifier::UNDERLINED)),
+                );
+                sv.push(Span::raw(" ").style(self.styles.untyped));
+            } else {
+                sv.push(Span::raw(missed + " ").style(self.styles.untyped));
+            }
+        }
+        Line::from(sv)
+    }
+
+    /// Create test from an iterator over string items
+    pub fn test_from(&mut self, words: impl Iterator<Item = String>) {
+        self.test_with_warmup(std::iter::empty(), words);
+    }
+
+    /// Create test from `warmup` words that are typed first but never scored, then the scored `words`
+    pub fn test_with_warmup(
+        &mut self,
+        warmup: impl Iterator<Item = String>,
+        words: impl Iterator<Item = String>,
+    ) {
+        let warmup = warmup.collect::<Vec<String>>();
+        self.warmup = warmup.len();
+        self.punctuator = Punctuator::new(self.seed);
+        self.words = warmup
+            .into_iter()
+            .chain(words)
+            .enumerate()
+            .map(|(i, w)| self.prepare(w, i == 0))
+            .collect::<Vec<TestWord>>();
+        self.set_at = Instant::now();
+        self.word_i = 0;
+        self.completed = false;
+        self.paused_at = None;
+        self.paused_for = Duration::ZERO;
+        self.aborted = false;
+        self.recent_errors.clear();
+        self.caps_warned = false;
+    }
+
+    /// Append words to the test, dropping typed words long behind the current one so endless tests stay bounded
+    pub fn extend(&mut self, words: impl Iterator<Item = String>) {
+        let words = words
+            .map(|w| self.prepare(w, false))
+            .collect::<Vec<TestWord>>();
+        self.words.extend(words);
+        let drop = self.word_i.saturating_sub(ENDLESS_KEPT);
+        if drop > 0 {
+            self.words.drain(..drop);
+            self.word_i -= drop;
+            self.warmup = self.warmup.saturating_sub(drop);
+        }
+    }
+
+    /// Word as it is to be typed, lowercased unless punctuated, with any injected punctuation, and reversed if configured
+    fn prepare(&mut self, w: String, first: bool) -> TestWord<'a> {
+        let w = if self.punctuated { w } else { w.to_lowercase() };
+        let w = if self.injects() {
+            self.punctuator.punctuate(w)
+        } else {
+            w
+        };
+        let w = if first && self.cfg.capitalize_first {
+            capitalize(&w)
+        } else {
+            w
+        };
+        if self.cfg.reverse {
+            w.chars().rev().collect::<String>().into()
+        } else {
+            w.into()
+        }
+    }
+
+    /// Part of `area` the test is drawn in, narrowed to `max_width`
+    fn text_area(&self, area: Rect) -> Rect {
+        if self.max_width == 0 || area.width <= self.max_width {
+            return area;
+        }
+        let x = match self.alignment {
+            TestAlignment::Left => area.x,
+            TestAlignment::Center => area.x + (area.width - self.max_width) / 2,
+        };
+        Rect {
+            x,
+            width: self.max_width,
+            ..area
+        }
+    }
+
+    /// Render the test text
+    pub fn render(&self, area: Rect, buf: &mut Buffer) {
+        let area = self.text_area(area);
+        let mut block = Block::new()
+            .borders(Borders::TOP)
+            .style(self.styles.accent)
+            .title(self.title.clone().bold()) // TODO this is annoying and bad
+            .padding(Padding::horizontal(1));
+        if self.completed {
+            block = block.title_bottom("Test complete! Press any key to see results.");
+        } else if self.paused_at.is_some() {
+            block = block.title_bottom("Paused");
+        } else if let Some(d) = self.reveal_left() {
+            block = block.title_bottom(format!("Hidden in {}s", d.as_secs() + 1));
+        } else if self.show_kps {
+            let kps = self.kps();
+            block = block.title_bottom(
+                Line::from(format!("{kps:.1} kps {}", "▮".repeat(kps.round() as usize)))
+                    .right_aligned(),
+            );
+        }
+
+        let inner = block.inner(area);
+        if self.lines_per_page > 0 {
+            let lines = self
+                .page_lines(block.inner(area).width as usize)
+                .into_iter()
+                .map(|r| {
+                    Line::from(
+                        r.flat_map(|i| self.tw_as_span_vec(i, &self.words[i]))
+                            .collect::<Vec<Span>>(),
+                    )
+                })
+                .collect::<Vec<Line>>();
+            Paragraph::new(lines)
+                .style(self.styles.root)
+                .block(block)
+                .render(area, buf);
+        } else {
+            Paragraph::new(self.words_to_line())
+                .style(self.styles.root)
+                .block(block)
+                .wrap(Wrap { trim: true })
+                .render(area, buf);
+        }
+        self.widen_cursor(inner, buf);
+    }
+
+    /// Extend the cursor over every cell of a wide glyph (CJK, emoji) under it.
+    /// Cells hidden behind a wide glyph are reset when it is drawn, so only its first cell would be highlighted
+    fn widen_cursor(&self, area: Rect, buf: &mut Buffer) {
+        // as the style reads back from a cell, with unset colors reset
+        let sty = Cell::default()
+            .set_style(self.styles.root.patch(self.styles.cursor))
+            .style();
+        for y in area.top()..area.bottom() {
+            for x in area.left()..area.right() {
+                let cell = &buf[(x, y)];
+                let width = cell.symbol().width() as u16;
+                if width < 2 || cell.style() != sty {
+                    continue;
+                }
+                for hidden in x + 1..min(x + width, area.right()) {
+                    buf[(hidden, y)].set_style(sty);
+                }
+                return;
+            }
+        }
+    }
+
+    /// Word ranges of each line on the page containing the current word, when laid out `width` wide.
+    /// Recomputed every render so that resizing moves page boundaries.
+    fn page_lines(&self, width: usize) -> Vec<Range<usize>> {
+        // greedily fill lines, a word fits if it does without its trailing space
+        let mut lines: Vec<Range<usize>> = Vec::new();
+        let mut start = 0;
+        let mut w = 0;
+        for (i, tw) in self.words.iter().enumerate() {
+            let len = max(tw.spans.len(), tw.word.chars().count());
+            if w > 0 && w + len > width {
+                lines.push(start..i);
+                start = i;
+                w = 0;
+            }
+            w += len + 1;
+        }
+        if start < self.words.len() {
+            lines.push(start..self.words.len());
+        }
+
+        let cur = lines
+            .iter()
+            .position(|r| r.contains(&self.word_i))
+            .unwrap_or(lines.len().saturating_sub(1));
+        let page = cur / self.lines_per_page * self.lines_per_page;
+        lines
+            .into_iter()
+            .skip(page)
+            .take(self.lines_per_page)
+            .collect()
+    }
+
+    /// Convert all testwords to styled spans with spacing, returned as a single line so that it wraps properly
+    pub fn words_to_line(&self) -> Line<'a> {
+        Line::from(
+            self.words
+                .iter()
+                .enumerate()
+                .flat_map(|(i, tw)| self.tw_as_span_vec(i, tw))
+                .collect::<Vec<Span>>(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::style::{Color, Style};
+    use std::sync::mpsc::{Receiver, sync_channel};
+
+    /// Build a test over `words`, the receiver must be kept alive for the test to send requests
+    fn new_test(cfg: TestCfg, words: &[&str]) -> (Test<'static>, Receiver<UiRequest>) {
+        build_test(|b| b.cfg(cfg), words)
+    }
+
+    /// Test of `words` configured by `f` from the default builder
+    fn build_test(
+        f: impl FnOnce(TestBuilder) -> TestBuilder,
+        words: &[&str],
+    ) -> (Test<'static>, Receiver<UiRequest>) {
+        let (tx, rx) = sync_channel(16);
+        let mut t = f(TestBuilder::new(Styles::default(), tx)).build();
+        t.test_from(words.iter().map(|w| w.to_string()));
+        (t, rx)
+    }
+
+    /// Send every char of `s` as a keypress
+    fn type_str(t: &mut Test, s: &str) {
+        for c in s.chars() {
+            t.handle_events(KeyEvent::from(KeyCode::Char(c)));
+        }
+    }
+
+    #[test]
+    fn test_keypress_correct() {
+        let tests: Vec<(&str, Vec<char>, bool)> = vec![
+            ("test", vec!['t', 'e', 's', 't'], true),
+            ("test", vec![' ', 'q', BKSPC, 't', 'e', 's', 't', ' '], true),
+            (
+                "test",
+                vec![
+                    't', 'e', 's', 't', 'a', 'b', 'c', WORD_BKSPC, 't', 'e', 's', 't', ' ',
+                ],
+                true,
+            ),
+            ("abcd", vec!['a', 'b', 'c', 'd', 'e'], false),
         ];
         for (word, chars, correct) in tests.into_iter() {
             let mut tw: TestWord = word.to_string().into();
-            tw.presses = chars.into_iter().map(|c| Keypress::from_chr(c)).collect();
+            tw.presses = chars
+                .into_iter()
+                .map(|c| Keypress::from_chr(c, true))
+                .collect();
             assert_eq!(tw.is_correct(), correct)
         }
     }
+
+    #[test]
+    fn test_keypress_correctness() {
+        let (mut t, _rx) = new_test(TestCfg::default(), &["café", "ab"]);
+        type_str(&mut t, "cafx");
+        t.handle_events(KeyEvent::from(KeyCode::Backspace));
+        type_str(&mut t, "é ax");
+        let correct = t
+            .words
+            .iter()
+            .flat_map(|tw| tw.presses.iter())
+            .map(|kp| kp.correct)
+            .collect::<Vec<bool>>();
+        assert_eq!(
+            correct,
+            vec![true, true, true, false, true, true, true, true, false]
+        );
+        assert_eq!(t.errors(), 2);
+    }
+
+    #[test]
+    fn test_keystroke_log() {
+        let (mut t, _rx) = new_test(TestCfg::default(), &["ab", "cd"]);
+        assert_eq!(t.keystroke_log(), "");
+        type_str(&mut t, "ax");
+        t.handle_events(KeyEvent::from(KeyCode::Backspace));
+        type_str(&mut t, "b c");
+        let start = t.started_at().unwrap();
+        for (i, kp) in t
+            .words
+            .iter_mut()
+            .flat_map(|tw| tw.presses.iter_mut())
+            .enumerate()
+        {
+            kp.time = start + Duration::from_millis(100 * i as u64);
+        }
+        let log = t.keystroke_log();
+        let lines = log.lines().collect::<Vec<&str>>();
+        assert_eq!(lines.len(), 6);
+        assert_eq!(
+            lines[1],
+            r#"{"ms":100,"key":"x","expected":"b","kind":"incorrect","word":0}"#
+        );
+        assert_eq!(
+            lines[2],
+            r#"{"ms":200,"key":"backspace","expected":null,"kind":"control","word":0}"#
+        );
+        assert_eq!(
+            lines[3],
+            r#"{"ms":300,"key":"b","expected":"b","kind":"correct","word":0}"#
+        );
+        assert_eq!(
+            lines[5],
+            r#"{"ms":500,"key":"c","expected":"c","kind":"correct","word":1}"#
+        );
+    }
+
+    #[test]
+    fn test_warmup() {
+        let (tx, _rx) = sync_channel(16);
+        let mut t = TestBuilder::new(Styles::default(), tx).build();
+        t.test_with_warmup(
+            ["go".to_string()].into_iter(),
+            ["ab", "cd"].map(String::from).into_iter(),
+        );
+        assert_eq!(t.word_count(), 2);
+        type_str(&mut t, "gx a");
+        assert_eq!(t.progress(), (0, 2));
+        assert_eq!(t.remaining_words(), vec!["ab", "cd"]);
+        type_str(&mut t, "b c");
+        assert_eq!(t.progress(), (1, 2));
+        assert_eq!(t.remaining_words(), vec!["cd"]);
+        type_str(&mut t, "d");
+        assert_eq!(t.progress(), (2, 2));
+        assert!(t.is_completed());
+        let start = Instant::now();
+        for (i, kp) in t
+            .words
+            .iter_mut()
+            .flat_map(|tw| tw.presses.iter_mut())
+            .enumerate()
+        {
+            kp.time = start + Duration::from_millis(100 * i as u64);
+        }
+
+        // the mistyped warmup word and its time don't count
+        assert_eq!(t.errors(), 0);
+        assert_eq!(t.accuracy(), 100.0);
+        assert_eq!(t.correct_chars(), 5);
+        assert_eq!(
+            t.word_results().collect::<Vec<_>>(),
+            vec![("ab", true), ("cd", true)]
+        );
+        assert_eq!((t.elapsed_secs() * 10.0).round(), 4.0);
+        assert_eq!(t.metrics().typed_chars, 5);
+    }
+
+    #[test]
+    fn test_cached_correct_matches_recomputed() {
+        let tests: Vec<(&str, Vec<char>)> = vec![
+            ("test", vec!['t', 'e', 's', 't']),
+            ("test", vec!['t', 'e', 's', 't', 'x']),
+            ("test", vec!['t', 'e', 's', 't', 'x', BKSPC]),
+            ("test", vec![' ', 'q', BKSPC, 't', 'e', 's', 't', ' ']),
+            ("test", vec!['a', 'b', WORD_BKSPC, 't', 'e', 's', 't']),
+            ("abcd", vec!['a', 'b', 'c', 'd', 'e']),
+            ("abcd", vec![BKSPC, BKSPC, 'a']),
+        ];
+        for (word, chars) in tests.into_iter() {
+            let mut tw: TestWord = word.to_string().into();
+            for c in chars {
+                tw.press(c);
+                assert_eq!(tw.correct, tw.is_correct(), "{word}: after {c:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_edit_history() {
+        let mut tw: TestWord = "test".to_string().into();
+        for c in [
+            't', 'e', 'x', 'y', BKSPC, BKSPC, 's', 't', 'q', WORD_BKSPC, ' ',
+        ] {
+            tw.press(c);
+        }
+        assert_eq!(
+            tw.edit_history(),
+            vec![
+                ('t', true),
+                ('e', true),
+                ('x', true),
+                ('y', true),
+                ('s', true),
+                ('t', true),
+                ('q', true),
+            ]
+        );
+
+        let mut tw: TestWord = "test".to_string().into();
+        for c in ['t', 'e', 'x', BKSPC, 's', 't'] {
+            tw.press(c);
+        }
+        assert_eq!(
+            tw.edit_history(),
+            vec![
+                ('t', false),
+                ('e', false),
+                ('x', true),
+                ('s', false),
+                ('t', false),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_builder() {
+        let (tx, rx) = sync_channel(16);
+        let mut t = TestBuilder::new(Styles::default(), tx)
+            .cfg(TestCfg {
+                freeze_on_complete: true,
+                ..Default::default()
+            })
+            .build();
+        t.test_from(["hi".to_string()].into_iter());
+        type_str(&mut t, "hi ");
+        assert!(t.is_completed());
+        assert!(rx.try_recv().is_err());
+
+        // any key proceeds
+        type_str(&mut t, "x");
+        assert!(matches!(
+            rx.try_recv(),
+            Ok(UiRequest::ChangeScreen(Screen::Results))
+        ));
+    }
+
+    #[test]
+    fn test_delete_key() {
+        let delete = KeyEvent::new(KeyCode::Delete, KeyModifiers::NONE);
+        let (mut t, _rx) = new_test(TestCfg::default(), &["hi", "there"]);
+        type_str(&mut t, "hx");
+        t.handle_events(delete);
+        assert_eq!(t.words[0].spans.len(), 2);
+
+        let cfg = TestCfg {
+            delete_as_backspace: true,
+            ..Default::default()
+        };
+        let (mut t, _rx) = new_test(cfg, &["hi", "there"]);
+        type_str(&mut t, "hx");
+        t.handle_events(delete);
+        assert_eq!(t.words[0].spans.len(), 1);
+        assert_eq!(t.words[0].presses.last().unwrap().key, BKSPC);
+    }
+
+    #[test]
+    fn test_caps_lock_warning() {
+        let cfg = TestCfg {
+            caps_lock_warning: true,
+            ..Default::default()
+        };
+        let (mut t, rx) = new_test(cfg, &["ab", "cd"]);
+        type_str(&mut t, "x");
+        assert!(rx.try_recv().is_err());
+        type_str(&mut t, "B");
+        assert!(matches!(
+            rx.try_recv(),
+            Ok(UiRequest::DisplayStatus(Priority::Warn, ..))
+        ));
+        // only once per test
+        type_str(&mut t, " CD");
+        assert!(rx.try_recv().is_err());
+
+        let (mut t, rx) = new_test(TestCfg::default(), &["ab"]);
+        type_str(&mut t, "AB");
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_restart() {
+        let (mut t, rx) = new_test(TestCfg::default(), &["hi", "there"]);
+        type_str(&mut t, "h");
+        t.handle_events(KeyEvent::new(KeyCode::Char('r'), KeyModifiers::CONTROL));
+        assert!(matches!(rx.try_recv(), Ok(UiRequest::NewTest)));
+        // not typed into the test
+        assert_eq!(t.words[0].spans.len(), 1);
+    }
+
+    #[test]
+    fn test_endless() {
+        let (mut t, rx) = build_test(|b| b.endless(true), &["hi", "there"]);
+        type_str(&mut t, "hi there");
+        t.handle_events(KeyEvent::from(KeyCode::Tab));
+        assert!(!t.is_completed());
+        assert!(rx.try_recv().is_err());
+
+        // typed words are only dropped once far enough behind
+        t.extend(["Next".to_string()].into_iter());
+        assert_eq!(t.words.len(), 3);
+        assert_eq!(t.words[2].word, "next");
+        type_str(&mut t, " next ");
+        let words = vec!["word".to_string(); ENDLESS_KEPT * 2];
+        t.extend(words.into_iter());
+        type_str(&mut t, "word ".repeat(ENDLESS_KEPT).as_str());
+        t.extend(std::iter::once("more".to_string()));
+        // the first 3 words were dropped
+        assert_eq!(t.word_i, ENDLESS_KEPT);
+        assert_eq!(t.words.len(), ENDLESS_KEPT * 2 + 1);
+        assert_eq!(t.words_left(), ENDLESS_KEPT);
+        assert!(t.wpm() >= 0.0);
+    }
+
+    #[test]
+    fn test_text_area() {
+        let (t, _rx) = new_test(TestCfg::default(), &["ab"]);
+        let area = Rect::new(2, 1, 100, 10);
+        assert_eq!(t.text_area(area), area);
+        let (t, _rx) = build_test(|b| b.max_width(40, TestAlignment::Left), &["ab"]);
+        assert_eq!(t.text_area(area), Rect::new(2, 1, 40, 10));
+        let (t, _rx) = build_test(|b| b.max_width(40, TestAlignment::Center), &["ab"]);
+        assert_eq!(t.text_area(area), Rect::new(32, 1, 40, 10));
+        // narrower areas are left alone
+        assert_eq!(t.text_area(Rect::new(0, 0, 30, 5)), Rect::new(0, 0, 30, 5));
+    }
+
+    #[test]
+    fn test_pages() {
+        let words = ["aa", "bb", "cc", "dd", "ee"];
+        let (mut t, _rx) = build_test(|b| b.lines_per_page(2), &words);
+        // "aa bb" fits in 5 columns
+        assert_eq!(t.page_lines(5), vec![0..2, 2..4]);
+        type_str(&mut t, "aa bb cc dd ");
+        assert_eq!(t.page_lines(5), vec![4..5]);
+        // resizing recomputes the boundaries
+        assert_eq!(t.page_lines(8), vec![0..3, 3..5]);
+
+        // a single line follows the cursor
+        let (mut t, _rx) = build_test(|b| b.lines_per_page(1), &words);
+        type_str(&mut t, "aa bb cc dd ");
+        assert_eq!(t.page_lines(8), vec![3..5]);
+        assert_eq!(t.page_lines(5), vec![4..5]);
+    }
+
+    #[test]
+    fn test_kps() {
+        let (mut t, _rx) = new_test(TestCfg::default(), &["hello", "world"]);
+        assert_eq!(t.kps(), 0.0);
+        type_str(&mut t, "hello w");
+        assert_eq!(t.kps(), 7.0 / KPS_WINDOW.as_secs_f64());
+    }
+
+    #[test]
+    fn test_min_live_accuracy() {
+        let (mut t, rx) = new_test(
+            TestCfg {
+                min_live_accuracy: 90.0,
+                ..Default::default()
+            },
+            &["abcdefghijklmnopqrstuvwxyz"],
+        );
+        // too few presses to judge
+        type_str(&mut t, "x");
+        assert!(!t.is_aborted());
+        type_str(&mut t, &"x".repeat(MIN_ACCURACY_PRESSES));
+        assert!(t.is_aborted());
+        assert!(!t.is_completed());
+        assert!(matches!(
+            rx.try_recv(),
+            Ok(UiRequest::DisplayStatus(Priority::Warn, ..))
+        ));
+        assert!(matches!(
+            rx.try_recv(),
+            Ok(UiRequest::ChangeScreen(Screen::Results))
+        ));
+        // further keys are ignored rather than queueing more requests
+        type_str(&mut t, "xX");
+        assert!(rx.try_recv().is_err());
+
+        // as many requests as the UI queues, with a caps lock warning on the aborting key
+        let (tx, rx) = sync_channel(crate::ui::REQUEST_QUEUE);
+        let mut t = TestBuilder::new(Styles::default(), tx)
+            .cfg(TestCfg {
+                min_live_accuracy: 90.0,
+                caps_lock_warning: true,
+                ..Default::default()
+            })
+            .build();
+        t.test_from(["a".repeat(MIN_ACCURACY_PRESSES * 2)].into_iter());
+        type_str(&mut t, &"x".repeat(MIN_ACCURACY_PRESSES - 1));
+        type_str(&mut t, "AAA");
+        assert!(t.is_aborted());
+        let requests = rx.try_iter().collect::<Vec<_>>();
+        assert_eq!(requests.len(), 3);
+        assert!(matches!(
+            requests[2],
+            UiRequest::ChangeScreen(Screen::Results)
+        ));
+    }
+
+    #[test]
+    fn test_word_wpm() {
+        let (mut t, _rx) = new_test(TestCfg::default(), &["ab", "cd", "ef"]);
+        type_str(&mut t, "ab xd ef");
+        // one keypress every 100ms
+        let start = Instant::now();
+        for (i, kp) in t
+            .words
+            .iter_mut()
+            .flat_map(|tw| tw.presses.iter_mut())
+            .enumerate()
+        {
+            kp.time = start + Duration::from_millis(100 * i as u64);
+        }
+        // 'cd' was typed incorrectly
+        assert_eq!(t.word_wpm(), vec![120.0, 120.0]);
+    }
+
+    #[test]
+    fn test_wpm_over_time() {
+        let (mut t, _rx) = new_test(TestCfg::default(), &["abcdef", "ghij"]);
+        assert_eq!(t.wpm_over_time(Duration::from_secs(1)), vec![]);
+        type_str(&mut t, "abcdef ghxj");
+        // one keypress every 100ms
+        let start = Instant::now();
+        for (i, kp) in t
+            .words
+            .iter_mut()
+            .flat_map(|tw| tw.presses.iter_mut())
+            .enumerate()
+        {
+            kp.time = start + Duration::from_millis(100 * i as u64);
+        }
+        // shorter than a bucket, 9 correct characters after the first
+        assert_eq!(t.wpm_over_time(Duration::from_secs(2)), vec![(1.0, 108.0)]);
+        let r = |v: Vec<(f64, f64)>| {
+            v.into_iter()
+                .map(|(s, w)| ((s * 10.0).round() / 10.0, w.round()))
+                .collect::<Vec<_>>()
+        };
+        // the remaining 200ms are merged into the last bucket, which has 'x' wrong
+        assert_eq!(
+            r(t.wpm_over_time(Duration::from_millis(400))),
+            vec![(0.4, 90.0), (1.0, 120.0)]
+        );
+        // a sliver past the last whole bucket would be 'j' alone in 10ms
+        assert_eq!(
+            r(t.wpm_over_time(Duration::from_millis(495))),
+            vec![(0.5, 97.0), (1.0, 119.0)]
+        );
+    }
+
+    #[test]
+    fn test_wpm() {
+        let (mut t, _rx) = new_test(TestCfg::default(), &["ab", "cd"]);
+        assert_eq!(t.wpm(), 0.0);
+        type_str(&mut t, "a");
+        assert_eq!(t.wpm(), 0.0);
+
+        type_str(&mut t, "b cd");
+        let start = Instant::now();
+        for (i, kp) in t
+            .words
+            .iter_mut()
+            .flat_map(|tw| tw.presses.iter_mut())
+            .enumerate()
+        {
+            kp.time = start + Duration::from_millis(100 * i as u64);
+        }
+        // 5 correct characters in 0.4s
+        assert_eq!(t.wpm().round(), 150.0);
+    }
+
+    #[test]
+    fn test_accuracy() {
+        let (mut t, _rx) = new_test(TestCfg::default(), &["abcd", "efgh"]);
+        assert_eq!(t.accuracy(), 100.0);
+        // spaces and backspaces don't count, the corrected error does
+        type_str(&mut t, "abxd");
+        t.handle_events(KeyEvent::from(KeyCode::Backspace));
+        t.handle_events(KeyEvent::from(KeyCode::Backspace));
+        type_str(&mut t, "cd ef");
+        assert_eq!(t.accuracy(), 87.5);
+    }
+
+    #[test]
+    fn test_error_fates() {
+        let (mut t, _rx) = new_test(TestCfg::default(), &["abcd", "efgh", "ijkl"]);
+        assert_eq!(t.error_fates(), (0, 0));
+        // fixed by backspacing, deleting a correct character along the way
+        type_str(&mut t, "abxd");
+        t.handle_events(KeyEvent::from(KeyCode::Backspace));
+        t.handle_events(KeyEvent::from(KeyCode::Backspace));
+        type_str(&mut t, "cd ");
+        assert_eq!(t.error_fates(), (1, 0));
+        // fixed by deleting the word, then left wrong
+        type_str(&mut t, "xy");
+        t.handle_events(KeyEvent::new(KeyCode::Backspace, KeyModifiers::CONTROL));
+        type_str(&mut t, "efxhz ixkl");
+        assert_eq!(t.error_fates(), (3, 3));
+    }
+
+    #[test]
+    fn test_hand_interval() {
+        let (mut t, _rx) = new_test(TestCfg::default(), &["ah", "j."]);
+        assert_eq!(t.hand_interval(Hand::Left), None);
+        type_str(&mut t, "ah j.");
+        let start = Instant::now();
+        for (i, kp) in t
+            .words
+            .iter_mut()
+            .flat_map(|tw| tw.presses.iter_mut())
+            .enumerate()
+        {
+            kp.time = start + Duration::from_millis(100 * i as u64 * i as u64);
+        }
+        // 'a' is first, so the left hand has nothing before it. The period isn't on the map
+        assert_eq!(t.hand_interval(Hand::Left), None);
+        // 'h' after 100ms, 'j' after 500ms
+        assert_eq!(t.hand_interval(Hand::Right), Some(300.0));
+    }
+
+    #[test]
+    fn test_strict_spaces() {
+        let (mut lax, _rx1) = new_test(TestCfg::default(), &["ab", "cd", "ef"]);
+        let (mut strict, _rx2) = new_test(
+            TestCfg {
+                strict_spaces: true,
+                ..Default::default()
+            },
+            &["ab", "cd", "ef"],
+        );
+        for t in [&mut lax, &mut strict] {
+            type_str(t, "ab  cd");
+        }
+
+        // the doubled space skipped "cd", so it was typed into "ef"
+        assert_eq!(lax.errors(), 2);
+        assert_eq!(lax.word_results().count(), 2);
+
+        assert_eq!(strict.errors(), 1);
+        assert_eq!(strict.word_i, 1);
+        assert!(strict.words[1].correct);
+        assert!(!strict.words[1].is_spaced());
+        type_str(&mut strict, " ");
+        assert_eq!(
+            strict.word_results().collect::<Vec<_>>(),
+            vec![("ab", true), ("cd", true)]
+        );
+    }
+
+    #[test]
+    fn test_lock_completed_words() {
+        let (mut t, _rx) = new_test(
+            TestCfg {
+                lock_completed_words: true,
+                ..Default::default()
+            },
+            &["ab", "cd", "ef"],
+        );
+        type_str(&mut t, "ax c");
+        for _ in 0..3 {
+            t.handle_events(KeyEvent::from(KeyCode::Backspace));
+        }
+        assert_eq!(t.word_i, 1);
+        assert!(t.words[1].spans.is_empty());
+        t.handle_events(KeyEvent::new(KeyCode::Backspace, KeyModifiers::CONTROL));
+        assert_eq!(t.word_i, 1);
+        // the mistyped word stays committed
+        assert_eq!(t.words[0].input, "ax");
+        assert!(t.words[0].is_spaced());
+    }
+
+    #[test]
+    fn test_word_bounds() {
+        let keys = [
+            KeyEvent::from(KeyCode::Char('a')),
+            KeyEvent::from(KeyCode::Char(' ')),
+            KeyEvent::from(KeyCode::Backspace),
+            KeyEvent::new(KeyCode::Backspace, KeyModifiers::CONTROL),
+        ];
+        let (mut t, rx) = new_test(TestCfg::default(), &[]);
+        for k in keys {
+            t.handle_events(k);
+        }
+        assert!(!t.is_completed());
+        assert!(rx.try_recv().is_err());
+
+        // nothing typed in the first word, so there is no word to go back to
+        let (mut t, _rx) = new_test(TestCfg::default(), &["ab", "cd"]);
+        t.handle_events(KeyEvent::new(KeyCode::Backspace, KeyModifiers::CONTROL));
+        t.handle_events(KeyEvent::new(KeyCode::Backspace, KeyModifiers::ALT));
+        t.handle_events(KeyEvent::from(KeyCode::Backspace));
+        assert_eq!(t.word_i, 0);
+        type_str(&mut t, "ab");
+        assert_eq!(t.words[0].input, "ab");
+
+        // typing past the last word of an endless test waits for more
+        let (mut t, _rx) = build_test(|b| b.endless(true), &["ab"]);
+        type_str(&mut t, "ab x");
+        assert_eq!(t.word_i, 1);
+        t.extend(std::iter::once("cd".to_string()));
+        type_str(&mut t, "c");
+        assert_eq!(t.words[1].input, "c");
+    }
+
+    #[test]
+    fn test_backspace_correct() {
+        let (mut t, _rx) = new_test(TestCfg::default(), &["ab", "cd", "ef"]);
+        type_str(&mut t, "ab cx ");
+        // a wrong word can be gone back into
+        t.handle_events(KeyEvent::from(KeyCode::Backspace));
+        assert_eq!(t.word_i, 1);
+        t.handle_events(KeyEvent::from(KeyCode::Backspace));
+        type_str(&mut t, "d ");
+        for _ in 0..4 {
+            t.handle_events(KeyEvent::from(KeyCode::Backspace));
+        }
+        assert_eq!(t.word_i, 2);
+        t.handle_events(KeyEvent::new(KeyCode::Backspace, KeyModifiers::CONTROL));
+        assert_eq!(t.word_i, 2);
+        assert_eq!(t.words[1].input, "cd");
+
+        let (mut t, _rx) = new_test(
+            TestCfg {
+                allow_backspace_correct: true,
+                ..Default::default()
+            },
+            &["ab", "cd"],
+        );
+        type_str(&mut t, "ab ");
+        t.handle_events(KeyEvent::from(KeyCode::Backspace));
+        assert_eq!(t.word_i, 0);
+    }
+
+    #[test]
+    fn test_recent_errors() {
+        let (mut t, _rx) = new_test(TestCfg::default(), &["abc", "def", "ghi"]);
+        type_str(&mut t, "xbcz drf gyy");
+        assert_eq!(
+            t.recent_errors().copied().collect::<Vec<_>>(),
+            vec![
+                (None, 'z'),
+                (Some('e'), 'r'),
+                (Some('h'), 'y'),
+                (Some('i'), 'y')
+            ]
+        );
+        type_str(&mut t, " ");
+        t.test_from(["abc".to_string()].into_iter());
+        assert_eq!(t.recent_errors().count(), 0);
+    }
+
+    #[test]
+    fn test_skip_word() {
+        let (mut t, _rx) = new_test(
+            TestCfg {
+                skip_word_key: "ctrl+s".to_string(),
+                ..Default::default()
+            },
+            &["awkward", "word"],
+        );
+        type_str(&mut t, "aw");
+        t.handle_events(KeyEvent::new(KeyCode::Char('s'), KeyModifiers::CONTROL));
+        assert_eq!(t.word_i, 1);
+        assert!(t.words[0].skipped);
+        assert!(!t.words[0].correct);
+
+        // skipping the last word finishes the test
+        t.handle_events(KeyEvent::new(KeyCode::Char('s'), KeyModifiers::CONTROL));
+        assert!(t.is_completed());
+        assert_eq!(t.correct_chars(), 0);
+
+        assert_eq!(
+            parse_key("Alt+N"),
+            Some((KeyCode::Char('n'), KeyModifiers::ALT))
+        );
+        assert_eq!(parse_key("f5"), Some((KeyCode::F(5), KeyModifiers::NONE)));
+        assert_eq!(parse_key(""), None);
+        assert_eq!(parse_key("ctrl+nope"), None);
+    }
+
+    #[test]
+    fn test_key_matches() {
+        let key = |c, m| KeyEvent::new(c, m);
+        // modifiers match exactly
+        assert!(key_matches(
+            "s",
+            &key(KeyCode::Char('s'), KeyModifiers::NONE)
+        ));
+        assert!(!key_matches(
+            "s",
+            &key(KeyCode::Char('s'), KeyModifiers::CONTROL)
+        ));
+        assert!(!key_matches(
+            "ctrl+s",
+            &key(KeyCode::Char('s'), KeyModifiers::NONE)
+        ));
+        assert!(!key_matches(
+            "ctrl+s",
+            &key(
+                KeyCode::Char('s'),
+                KeyModifiers::CONTROL | KeyModifiers::ALT
+            )
+        ));
+        // ctrl and alt don't care for case, other characters do
+        assert!(key_matches(
+            "Ctrl+R",
+            &key(KeyCode::Char('r'), KeyModifiers::CONTROL)
+        ));
+        assert!(key_matches(
+            "Q",
+            &key(KeyCode::Char('Q'), KeyModifiers::SHIFT)
+        ));
+        assert!(!key_matches(
+            "Q",
+            &key(KeyCode::Char('q'), KeyModifiers::NONE)
+        ));
+        assert!(!key_matches(
+            "q",
+            &key(KeyCode::Char('Q'), KeyModifiers::SHIFT)
+        ));
+        assert!(key_matches(
+            "shift+tab",
+            &key(KeyCode::BackTab, KeyModifiers::SHIFT)
+        ));
+        assert!(!key_matches(
+            "tab",
+            &key(KeyCode::BackTab, KeyModifiers::SHIFT)
+        ));
+        // several keys
+        assert!(key_matches("esc q", &key(KeyCode::Esc, KeyModifiers::NONE)));
+        assert!(key_matches(
+            "esc q",
+            &key(KeyCode::Char('q'), KeyModifiers::NONE)
+        ));
+        assert!(!key_matches("", &key(KeyCode::Esc, KeyModifiers::NONE)));
+
+        assert!(types_text(KeyCode::Char('Q'), KeyModifiers::SHIFT));
+        assert!(!types_text(KeyCode::Char('q'), KeyModifiers::ALT));
+        assert!(!types_text(KeyCode::Esc, KeyModifiers::NONE));
+    }
+
+    #[test]
+    fn test_replay_word() {
+        let (mut t, _rx) = new_test(TestCfg::default(), &["one", "two"]);
+        type_str(&mut t, "one tx");
+        t.handle_events(KeyEvent::from(KeyCode::Backspace));
+        type_str(&mut t, "w");
+        let keys = t.partial_keys();
+        assert_eq!(keys, vec!['t', 'x', BKSPC, 'w']);
+
+        let (mut resumed, _rx) = new_test(TestCfg::default(), &["two"]);
+        assert!(resumed.replay_word(&keys));
+        assert_eq!(resumed.words[0].input, "tw");
+        assert_eq!(resumed.words[0].spans.len(), 2);
+        // the pause keeps time from running until typing resumes
+        assert!(resumed.paused_at.is_some());
+        type_str(&mut resumed, "o ");
+        assert!(resumed.is_completed());
+        assert!(resumed.words[0].correct);
+
+        // keys that can't be of one word leave it to be typed from its start
+        let (mut t, _rx) = new_test(TestCfg::default(), &["two"]);
+        assert!(!t.replay_word(&['t', ' ', 'w']));
+        assert!(!t.replay_word(&['\n']));
+        assert!(!t.is_started());
+    }
+
+    #[test]
+    fn test_custom_keys() {
+        let (mut t, _rx) = new_test(TestCfg::default(), &["ab", "cd"]);
+        t.keys.next_word = "Enter".to_string();
+        t.keys.backspace = "Ctrl+h".to_string();
+        type_str(&mut t, "ax");
+        t.handle_events(KeyEvent::new(KeyCode::Backspace, KeyModifiers::NONE));
+        t.handle_events(KeyEvent::new(KeyCode::Char('h'), KeyModifiers::CONTROL));
+        type_str(&mut t, "b");
+        t.handle_events(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+        assert_eq!(t.word_i, 1);
+        assert!(t.words[0].correct);
+        // space no longer moves on
+        type_str(&mut t, " ");
+        assert_eq!(t.word_i, 1);
+    }
+
+    #[test]
+    fn test_metrics() {
+        let (mut t, _rx) = new_test(TestCfg::default(), &["abcd", "efgh", "ijkl"]);
+        type_str(&mut t, "abcd efxh ijkl");
+        let mut m = t.metrics();
+        assert_eq!(m.correct_chars, 9);
+        assert_eq!(m.correct_spaces, 1);
+        assert_eq!(m.typed_chars, 14);
+        assert_eq!(m.correct_words, 2);
+        assert_eq!(m.uncorrected_errors, 1);
+        assert_eq!((m.char_presses, m.errors), (12, 1));
+
+        m.secs = 6.0;
+        let r = |w: f64| (w * 10.0).round() / 10.0;
+        assert_eq!(r(m.net_wpm()), 18.0);
+        assert_eq!(r(m.no_space_wpm()), 16.0);
+        assert_eq!(r(m.gross_wpm()), 28.0);
+        assert_eq!(r(m.ansi_wpm()), 18.0);
+        assert_eq!(r(m.literal_wpm()), 20.0);
+        assert_eq!(r(m.accuracy()), 91.7);
+    }
+
+    #[test]
+    fn test_inject_punctuation() {
+        let cfg = TestCfg {
+            inject_punctuation: true,
+            ..Default::default()
+        };
+        let words = ["the"; 50];
+        let (mut t, _rx) = new_test(cfg.clone(), &words);
+        let injected = t.words.iter().map(|tw| tw.word.clone()).collect::<Vec<_>>();
+        assert_eq!(injected[0], "The");
+        assert!(injected.iter().any(|w| w.ends_with(',')));
+        assert!(injected.windows(2).all(|w| {
+            let ends = w[0].ends_with(['.', '?', '!']);
+            ends == w[1].trim_start_matches('"').starts_with('T')
+        }));
+
+        // the same seed injects the same
+        let (mut again, _rx) = new_test(cfg, &[]);
+        again.test_from(words.map(String::from).into_iter());
+        assert!(again.words.iter().map(|tw| &tw.word).eq(injected.iter()));
+        t.set_seed(1);
+        t.test_from(words.map(String::from).into_iter());
+        assert!(!t.words.iter().map(|tw| &tw.word).eq(injected.iter()));
+        assert!(t.is_punctuated());
+
+        // scored against the injected form, recorded without it
+        let first = t.words[0].word.clone();
+        type_str(&mut t, &format!("{first} the"));
+        assert!(t.words[0].correct);
+        assert_eq!(t.word_results().next(), Some(("The", true)));
+
+        // words that already have punctuation are left alone
+        t.set_punctuated(true);
+        t.test_from(["one".to_string()].into_iter());
+        assert_eq!(t.words[0].word, "one");
+    }
+
+    #[test]
+    fn test_obscure() {
+        let cfg = TestCfg {
+            obscure: true,
+            obscure_reveal_secs: 60,
+            ..Default::default()
+        };
+        let (mut t, _rx) = new_test(cfg, &["ab", "cd"]);
+        let text = |t: &Test| {
+            (0..t.words.len())
+                .flat_map(|i| t.tw_as_span_vec(i, &t.words[i]))
+                .map(|s| s.content.to_string())
+                .collect::<String>()
+        };
+        // revealed until typing starts
+        assert!(t.reveal_left().is_some());
+        assert_eq!(text(&t), "ab cd ");
+
+        // scored against the real words
+        type_str(&mut t, "ab c");
+        assert!(t.reveal_left().is_none());
+        assert_eq!(text(&t), "ab c· ");
+        assert!(t.words[0].correct);
+
+        t.cfg.obscure_reveal_secs = 0;
+        t.test_from(["ef".to_string()].into_iter());
+        assert_eq!(text(&t), "·· ");
+    }
+
+    #[test]
+    fn test_chunks() {
+        let styled = |chunk_size| {
+            let (mut t, rx) = build_test(|b| b.chunk_size(chunk_size), &["abcdefg"]);
+            t.styles.cursor = Style::new().fg(Color::Red);
+            t.styles.chunk = Style::new().bg(Color::DarkGray);
+            (t, rx)
+        };
+        let (t, _rx) = styled(0);
+        let spans = |t: &Test| {
+            t.tw_as_span_vec(0, &t.words[0])
+                .into_iter()
+                .map(|s| (s.content.to_string(), s.style == t.styles.chunk))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            spans(&t),
+            vec![("a".to_string(), false), ("bcdefg ".to_string(), false)]
+        );
+
+        let (mut t, _rx) = styled(2);
+        type_str(&mut t, "a");
+        let s = |c: &str, shaded| (c.to_string(), shaded);
+        // typed and cursor styles are kept, chunks count from the start of the word
+        assert_eq!(
+            spans(&t),
+            vec![
+                s("a", false),
+                s("b", false),
+                s("cd", true),
+                s("ef", false),
+                s("g", true),
+                s(" ", false)
+            ]
+        );
+        assert_eq!(t.tw_as_span_vec(0, &t.words[0])[1].style, t.styles.cursor);
+    }
+
+    #[test]
+    fn test_highlight_correct() {
+        let (mut t, _rx) = build_test(|b| b.highlight_correct(false), &["ab"]);
+        t.styles.typed = Style::new().fg(Color::DarkGray);
+        t.styles.incorrect = Style::new().fg(Color::Red);
+        type_str(&mut t, "ax");
+        assert_eq!(t.words[0].spans[0].style, t.styles.untyped);
+        assert_eq!(t.words[0].spans[1].style, t.styles.incorrect);
+    }
+
+    #[test]
+    fn test_punctuated() {
+        let (tx, _rx) = sync_channel(16);
+        let mut t = TestBuilder::new(Styles::default(), tx).build();
+        t.test_from(["Don't,".to_string()].into_iter());
+        assert_eq!(t.words().collect::<Vec<&str>>(), vec!["don't,"]);
+
+        for (s, correct) in [("don't, ", false), ("Don't, ", true)] {
+            t.set_punctuated(true);
+            t.test_from(["Don't,".to_string()].into_iter());
+            assert_eq!(t.words().collect::<Vec<&str>>(), vec!["Don't,"]);
+            type_str(&mut t, s);
+            assert_eq!(
+                t.word_results().collect::<Vec<_>>(),
+                vec![("Don't,", correct)]
+            );
+        }
+    }
+
+    #[test]
+    fn test_multibyte_words() {
+        let (mut t, _rx) = new_test(TestCfg::default(), &["café", "naïve"]);
+        let text = |t: &Test| {
+            t.words_to_line()
+                .spans
+                .iter()
+                .map(|s| s.content.to_string())
+                .collect::<String>()
+        };
+        // partially typed, with the cursor on and past a multi-byte character
+        for (s, shown) in [
+            ("caf", "café naïve "),
+            ("é", "café naïve "),
+            (" na", "café naïve "),
+            ("x", "café naxve "),
+        ] {
+            type_str(&mut t, s);
+            assert_eq!(text(&t), shown);
+        }
+    }
+
+    #[test]
+    fn test_wide_cursor() {
+        let (mut t, _rx) = new_test(TestCfg::default(), &["字a"]);
+        t.styles.cursor = Style::new().reversed();
+        let area = Rect::new(0, 0, 10, 2);
+        let mut buf = Buffer::empty(area);
+        t.render(area, &mut buf);
+        // top border, then padding
+        let sty = Cell::default()
+            .set_style(t.styles.root.patch(t.styles.cursor))
+            .style();
+        assert_eq!(buf[(1, 1)].symbol(), "字");
+        assert_eq!(buf[(1, 1)].style(), sty);
+        assert_eq!(buf[(2, 1)].style(), sty);
+        assert_ne!(buf[(3, 1)].style(), sty);
+
+        // narrow characters are left alone
+        type_str(&mut t, "字");
+        let mut buf = Buffer::empty(area);
+        t.render(area, &mut buf);
+        assert_eq!(buf[(3, 1)].symbol(), "a");
+        assert_eq!(buf[(3, 1)].style(), sty);
+        assert_ne!(buf[(4, 1)].style(), sty);
+    }
+
+    #[test]
+    fn test_ignore_overflow() {
+        let (mut strict, _rx1) = new_test(TestCfg::default(), &["ab", "cd", "ef"]);
+        let (mut lenient, _rx2) = new_test(
+            TestCfg {
+                ignore_overflow: true,
+                ..Default::default()
+            },
+            &["ab", "cd", "ef"],
+        );
+        for t in [&mut strict, &mut lenient] {
+            type_str(t, "abb cd ");
+        }
+
+        assert_eq!(strict.errors(), 1);
+        assert_eq!(strict.correct_chars(), 2);
+        assert_eq!(lenient.errors(), 0);
+        assert_eq!(lenient.correct_chars(), 5);
+        // space still advances
+        assert_eq!(lenient.word_i, 2);
+    }
+
+    #[test]
+    fn test_burst_percent() {
+        let (mut t, _rx) = new_test(TestCfg::default(), &["abcde"]);
+        assert_eq!(t.burst_percent(Duration::from_millis(100)), None);
+        type_str(&mut t, "abcde");
+        let start = Instant::now();
+        for (kp, ms) in t.words[0].presses.iter_mut().zip([0, 50, 300, 350, 600]) {
+            kp.time = start + Duration::from_millis(ms);
+        }
+        assert_eq!(t.burst_percent(Duration::from_millis(100)), Some(50.0));
+    }
+
+    #[test]
+    fn test_capitalize_first() {
+        let (mut t, _rx) = new_test(
+            TestCfg {
+                capitalize_first: true,
+                ..Default::default()
+            },
+            &["Hello", "World"],
+        );
+        assert_eq!(t.words().collect::<Vec<&str>>(), vec!["Hello", "world"]);
+
+        // lowercase first letter is incorrect
+        type_str(&mut t, "hello");
+        assert!(!t.words[0].correct);
+        t.handle_events(KeyEvent::new(KeyCode::Backspace, KeyModifiers::CONTROL));
+        type_str(&mut t, "Hello");
+        assert!(t.words[0].correct);
+    }
+
+    #[test]
+    fn test_pause_excluded_from_elapsed() {
+        let (mut t, _rx) = new_test(TestCfg::default(), &["ab"]);
+        type_str(&mut t, "a");
+        t.pause();
+        std::thread::sleep(Duration::from_millis(100));
+        t.resume();
+        type_str(&mut t, "b");
+        assert!(t.elapsed_secs() < 0.05);
+    }
+
+    #[test]
+    fn test_reverse() {
+        let (mut t, rx) = new_test(
+            TestCfg {
+                reverse: true,
+                ..Default::default()
+            },
+            &["test"],
+        );
+        assert_eq!(t.words().collect::<Vec<&str>>(), vec!["tset"]);
+        assert_eq!(t.remaining_words(), vec!["test"]);
+
+        type_str(&mut t, "test");
+        assert!(!t.words[0].correct);
+        t.handle_events(KeyEvent::new(KeyCode::Backspace, KeyModifiers::CONTROL));
+        type_str(&mut t, "tset ");
+        assert!(t.words[0].correct);
+        assert!(t.is_completed());
+        assert!(rx.try_recv().is_ok());
+    }
+
+    #[test]
+    fn test_leading_space() {
+        let (mut t, _rx) = new_test(TestCfg::default(), &["a", "b"]);
+        type_str(&mut t, " ");
+        assert_eq!(t.word_i, 0);
+        assert!(!t.is_started());
+
+        let (mut t, _rx) = new_test(
+            TestCfg {
+                count_leading_space: true,
+                ..Default::default()
+            },
+            &["a", "b"],
+        );
+        type_str(&mut t, " ");
+        assert_eq!(t.word_i, 1);
+        assert!(t.is_started());
+    }
+
+    #[test]
+    fn test_completion_edge_cases() {
+        // empty
+        let (mut t, rx) = new_test(TestCfg::default(), &[]);
+        type_str(&mut t, "a ");
+        t.handle_events(KeyEvent::from(KeyCode::Backspace));
+        assert!(!t.is_completed());
+        assert!(rx.try_recv().is_err());
+
+        // single word needs the trailing space
+        let (mut t, rx) = new_test(TestCfg::default(), &["hi"]);
+        type_str(&mut t, "hi");
+        assert!(!t.is_completed());
+        type_str(&mut t, " ");
+        assert!(t.is_completed());
+        assert!(rx.try_recv().is_ok());
+
+        // last of many words completes on the last correct char
+        let (mut t, _rx) = new_test(TestCfg::default(), &["a", "b"]);
+        type_str(&mut t, "a b");
+        assert!(t.is_completed());
+    }
 }
@@ -1,40 +1,143 @@
 //! Root UI
-use crate::{config::Config, lang::Lang, test::Test};
+use crate::{
+    calibration::{Calibration, Goal},
+    config::{Config, KeybindsCfg, ThemeCfg},
+    lang::{Lang, LangError},
+    nemesis::Nemesis,
+    practice::Practice,
+    recovery::Recovery,
+    results::{self, Mode, Record, Summary},
+    status::{Priority, StatusBar},
+    streak::Streak,
+    test::{Hand, Test, TestBuilder, key_matches, parse_key, types_text},
+};
 use chrono::{DateTime, Local, TimeDelta, Timelike};
 use ratatui::{
+    DefaultTerminal,
     buffer::Buffer,
     crossterm::{
         event::{
-            self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers, KeyboardEnhancementFlags,
-            PopKeyboardEnhancementFlags, PushKeyboardEnhancementFlags, poll,
+            self, DisableFocusChange, EnableFocusChange, Event, KeyEvent, KeyEventKind,
+            KeyboardEnhancementFlags, PopKeyboardEnhancementFlags, PushKeyboardEnhancementFlags,
+            poll,
         },
         execute,
+        terminal::SetTitle,
     },
-    layout::{Constraint, Layout, Rect},
-    style::{Modifier, Style},
+    layout::{Constraint, Layout, Margin, Rect},
+    style::{Color, Modifier, Style, Stylize},
     text::{Line, Span},
-    widgets::{Paragraph, Widget},
+    widgets::{
+        Bar, BarChart, Block, Borders, Cell, Clear, Padding, Paragraph, Row, Sparkline, Table,
+        Widget, Wrap,
+    },
 };
+use serde::{Deserialize, Serialize};
 use std::{
+    collections::VecDeque,
+    fs,
     io::stdout,
+    process::Command,
     sync::mpsc::{Receiver, SyncSender, sync_channel},
+    time::{Duration, Instant},
 };
-use strum::{Display, EnumIter, FromRepr};
+use strum::{Display, EnumIter, EnumString, FromRepr, VariantNames};
+
+/// Seconds each idle statistic is shown for
+const IDLE_ROTATE_SECS: u64 = 5;
+
+/// Word counts selectable with `keybinds.presets`, Alt+1 through Alt+4 by default
+const WORD_COUNT_PRESETS: [u32; 4] = [10, 25, 50, 100];
+
+/// Tests with fewer correct words list each word's speed instead of a histogram
+const HISTOGRAM_MIN_WORDS: usize = 5;
+
+/// Most buckets in the per-word speed histogram, wider buckets are used past this
+const HISTOGRAM_MAX_BUCKETS: usize = 12;
+
+/// Time each bar of the speed timeline covers
+const TIMELINE_BUCKET: Duration = Duration::from_secs(1);
+
+/// Length of the results screen's reveal animation
+const RESULTS_ANIMATION: Duration = Duration::from_millis(300);
+
+/// Rows the results screen slides up by as it is revealed
+const RESULTS_SLIDE_ROWS: f64 = 3.0;
+
+/// Time between frames while animating
+const ANIMATION_FRAME: Duration = Duration::from_millis(16);
+
+/// How long the metronome is lit at the start of each beat, at most half the beat
+const METRONOME_FLASH: Duration = Duration::from_millis(100);
+
+/// Requests queued to the UI before more are dropped. A single keypress can send a few, such as
+/// statuses ahead of a change of screen
+pub const REQUEST_QUEUE: usize = 8;
+
+/// Words queued ahead in zen mode, topped up once half have been typed
+const ZEN_BUFFER: usize = 100;
 
 /// Fat UI struct is poorly named, basically is just the whole program besides config loading.
 pub struct Ui<'a> {
     cfg: Config,
     lang: Lang,
+    /// Fixed words tested every time instead of generating them from `lang`
+    custom_words: Option<Vec<String>>,
+    /// Problem words for spaced repetition, updated after every recorded test
+    practice: Practice,
+    /// Words missed most often
+    nemesis: Nemesis,
+    /// Speed to beat, shown on the results screen
+    goal: Option<Goal>,
+    /// Consecutive days practiced
+    streak: Streak,
+    /// Test interrupted in an earlier session, until resumed
+    recovery: Option<Recovery>,
+    /// When the test in progress was last autosaved, if it has been
+    autosaved: Option<Instant>,
+    /// Calibration in progress, or finished and being shown
+    calibration: Option<Calibration>,
 
     state: State,
     screen: Screen,
     last_screen: Screen,
 
     test: Test<'a>,
+    /// Seed the current test's words were generated from
+    seed: u64,
+    /// Where the current test's words came from
+    mode: Mode,
+
+    /// Result history, loaded when entering the statistics screen
+    history: Vec<Record>,
+    /// Only show statistics for results with this label
+    stats_label: Option<String>,
+    /// Show deleted characters in the results word review
+    review_deleted: bool,
+    /// Show speed under other common WPM definitions on the results screen
+    show_wpm_defs: bool,
+    /// Built-in theme being previewed on the themes screen
+    theme_i: usize,
+    /// Tests completed this session
+    completed_tests: usize,
+    /// Current test is in history already, so it isn't recorded again
+    recorded: bool,
+    /// Current test is an unrecorded warmup
+    warmup: bool,
+    /// Time of the last key event, for idling
+    last_input: Instant,
+    /// Showing idle statistics over the current screen
+    idling: bool,
+    /// When the results screen was entered, cleared on any key to cancel auto-advancing
+    results_shown: Option<Instant>,
+    /// When the screen last changed
+    screen_changed: Instant,
+    /// Time shown by the clock with `ui.lazy_clock`, updated on events and screen changes
+    clock: DateTime<Local>,
+    /// Requests in the order received, waiting for the current screen to have been shown long enough
+    deferred: VecDeque<UiRequest>,
 
-    status: String,
-    /// When the status message is to be cleared
-    clear_status_at: DateTime<Local>,
+    status: StatusBar,
 
     /// Text and widget styles, distilled from cfg
     pub styles: Styles,
@@ -51,31 +154,145 @@ pub enum State {
     Stopped,
 }
 
-#[derive(Default, Display, Clone, FromRepr, EnumIter)]
+#[derive(Default, Display, Clone, PartialEq, EnumIter)]
 /// Screen to display in body area
 pub enum Screen {
     #[default]
     #[strum(to_string = "Testing")]
-    TestScreen,
+    Test,
     #[strum(to_string = "Results")]
-    ResultsScreen,
+    Results,
     #[strum(to_string = "Statistics")]
-    StatisticsScreen,
+    Statistics,
     #[strum(to_string = "About")]
-    AboutScreen,
+    About,
+    #[strum(to_string = "Themes")]
+    Theme,
+    #[strum(to_string = "Calibration")]
+    Calibration,
+}
+
+impl Screen {
+    /// Screens reached by cycling with Tab, in order. The rest are entered for a purpose
+    const CYCLE: [Screen; 4] = [Self::Test, Self::Results, Self::Statistics, Self::About];
+
+    /// The following or preceding screen of those cycled through, wrapping around.
+    /// Screens outside the cycle continue from its last
+    fn cycle(&self, forward: bool) -> Self {
+        let n = Self::CYCLE.len();
+        let i = Self::CYCLE.iter().position(|s| s == self).unwrap_or(n - 1);
+        let next = if forward {
+            (i + 1) % n
+        } else {
+            (i + n - 1) % n
+        };
+        Self::CYCLE[next].clone()
+    }
+
+    /// Most useful keys on this screen and what they do, for the hint bar. Only the first of several
+    /// keys bound to an action is shown, and unbound keys are left out
+    fn hints<'k>(&self, kb: &'k KeybindsCfg) -> Vec<(&'k str, &'static str)> {
+        let hints: &[(&str, &str)] = match self {
+            Self::Test => &[
+                (&kb.end_test, "results"),
+                (&kb.previous_screen, "previous screen"),
+                (&kb.restart, "restart"),
+                (&kb.help, "help"),
+            ],
+            Self::Results => &[
+                (&kb.review_deleted, "deleted"),
+                (&kb.wpm_definitions, "definitions"),
+                (&kb.export_keystrokes, "export keystrokes"),
+                (&kb.presets[0], "new test"),
+                (&kb.next_screen, "next screen"),
+            ],
+            Self::Statistics => &[
+                (&kb.browse_next, "label"),
+                (&kb.export_report, "export"),
+                (&kb.next_screen, "next screen"),
+                (&kb.back, "back"),
+            ],
+            Self::About => &[(&kb.next_screen, "next screen"), (&kb.back, "back")],
+            Self::Theme => &[
+                (&kb.browse_next, "theme"),
+                (&kb.confirm, "apply"),
+                (&kb.back, "back"),
+            ],
+            Self::Calibration => &[(&kb.confirm, "set goal"), (&kb.back, "discard")],
+        };
+        hints
+            .iter()
+            .filter_map(|&(k, what)| Some((k.split_whitespace().next()?, what)))
+            .collect()
+    }
+}
+
+/// Section of the results screen, chosen and ordered by `results.widgets`
+#[derive(PartialEq, EnumString, VariantNames)]
+#[strum(serialize_all = "lowercase")]
+pub enum ResultsWidget {
+    /// Speed, and notes such as an unrecorded warmup
+    Speed,
+    /// Percentage of burst keypresses
+    Burst,
+    /// Errors fixed with backspace and those left in place
+    Errors,
+    /// Average time between keys by the hand that presses them
+    Hands,
+    /// Speed under other WPM definitions, toggled with 'w'
+    Definitions,
+    /// Speed through the test, second by second
+    Timeline,
+    /// Distribution of per-word speeds
+    Histogram,
+    /// Test as typed
+    Review,
 }
 
 /// Request sent by screens to here
 pub enum UiRequest {
     /// Change the screen (duh)
     ChangeScreen(Screen),
-    //// Set the statusbar to this message. Will overwrite any existing message
-    //DisplayStatus(String, DateTime<Local>),
-    //// Discard current test and create a new one
-    //NewTest,
+    /// Show a message in the statusbar for a while, as with `StatusBar::push`
+    DisplayStatus(Priority, String, TimeDelta),
+    /// Discard current test and create a new one
+    NewTest,
+    /// Suspend the TUI to edit the config file, reloading it afterwards
+    EditConfig,
+}
+
+/// How the caret over the next character to type is drawn
+#[derive(Deserialize, Serialize, Clone, Copy, Default, Display, FromRepr)]
+#[serde(rename_all = "lowercase")]
+pub enum CaretStyle {
+    /// Accent background behind the character
+    #[default]
+    Block,
+    /// Bold accent character, as terminals can't draw a bar between cells
+    Bar,
+    /// Accent underline beneath the character
+    Underline,
 }
 
-#[derive(Clone)]
+impl CaretStyle {
+    /// Style of the caret over text in `root` style
+    fn style(self, root: Style, accent: Color) -> Style {
+        match self {
+            Self::Block => root.bg(accent),
+            Self::Bar => root.fg(accent).add_modifier(Modifier::BOLD),
+            Self::Underline => root
+                .underline_color(accent)
+                .add_modifier(Modifier::UNDERLINED),
+        }
+    }
+
+    /// The following style, wrapping around
+    fn next(self) -> Self {
+        Self::from_repr(self as usize + 1).unwrap_or_default()
+    }
+}
+
+#[derive(Clone, Default)]
 pub struct Styles {
     pub root: Style,
     pub modeline: Style,
@@ -85,21 +302,32 @@ pub struct Styles {
     pub typed: Style,
     pub incorrect: Style,
     pub cursor: Style,
+    /// Untyped text in every other chunk of a word
+    pub chunk: Style,
 }
 
-impl Ui<'_> {
-    pub fn new(cfg: Config) -> Result<Self, std::io::Error> {
-        let lang = Lang::get_by_name(&cfg.lang)?;
+impl Styles {
+    /// Distill styles from the theme and UI config
+    fn new(cfg: &Config) -> Self {
+        Self::of_theme(&cfg.theme, cfg.ui.caret)
+    }
 
-        let root_sty = Style::new().fg(cfg.theme.fg).bg(cfg.theme.bg);
-        let mode_sty = root_sty.bg(cfg.theme.accent);
+    /// Distill styles from a theme
+    fn of_theme(theme: &ThemeCfg, caret: CaretStyle) -> Self {
+        let root_sty = Style::new().fg(theme.fg).bg(theme.bg);
+        let mode_sty = root_sty.bg(theme.accent);
         let mode_inv_sty = mode_sty.add_modifier(Modifier::REVERSED);
-        let accent_sty = root_sty.fg(cfg.theme.accent);
-        let untyped_sty = root_sty.fg(cfg.theme.untyped_text);
-        let typed_sty = root_sty.fg(cfg.theme.typed_text);
-        let incorrect_sty = root_sty.fg(cfg.theme.incorrect_text);
-        let cursor_sty = root_sty.bg(cfg.theme.accent);
-        let styles = Styles {
+        let accent_sty = root_sty.fg(theme.accent);
+        let untyped_sty = root_sty.fg(theme.untyped_text);
+        let typed_sty = root_sty.fg(theme.typed_text);
+        let incorrect_sty = root_sty.fg(theme.incorrect_text);
+        let cursor_sty = caret.style(root_sty, theme.accent);
+        // dim instead when the background can't be shaded
+        let chunk_sty = match theme.shade() {
+            Some(c) => untyped_sty.bg(c),
+            None => untyped_sty.add_modifier(Modifier::DIM),
+        };
+        Self {
             root: root_sty,
             modeline: mode_sty,
             modeline_inv: mode_inv_sty,
@@ -108,100 +336,1581 @@ impl Ui<'_> {
             typed: typed_sty,
             incorrect: incorrect_sty,
             cursor: cursor_sty,
-        };
+            chunk: chunk_sty,
+        }
+    }
+}
+
+impl<'a> Ui<'a> {
+    pub fn new(cfg: Config) -> Result<Self, LangError> {
+        let lang = Lang::get_by_name(&cfg.lang, &cfg.lang_dirs())?;
+        Ok(Self::with_lang(cfg, lang, None))
+    }
 
-        let (tx, rx) = sync_channel::<UiRequest>(2); // 2 to avoid lockups that should never happen anyways
-        Ok(Self {
-            styles: styles.clone(),
-            test: Test::new(styles, tx.clone()),
+    /// Test exactly `words`, in order, instead of loading a language.
+    /// The entry point for embedding the typing test with any word source, such as messages or
+    /// custom drills.
+    pub fn with_words(cfg: Config, words: Vec<String>) -> Self {
+        let lang = Lang::from_words("custom", words.clone());
+        Self::with_lang(cfg, lang, Some(words))
+    }
+
+    fn with_lang(mut cfg: Config, mut lang: Lang, custom_words: Option<Vec<String>>) -> Self {
+        let mut status = StatusBar::default();
+        status.push(
+            Priority::Info,
+            format!(
+                "Welcome to arstyper! Press <{}> for help, or '{}' to exit.",
+                cfg.keybinds.help, cfg.keybinds.quit
+            ),
+            TimeDelta::seconds(5),
+        );
+        let found = Lang::find(&lang.name, &cfg.lang_dirs());
+        if custom_words.is_none() && found.len() > 1 {
+            status.push(
+                Priority::Warn,
+                format!(
+                    "{} is in more than one language directory, using {}.",
+                    lang.name,
+                    found[0].display()
+                ),
+                TimeDelta::seconds(5),
+            );
+        }
+
+        Self::configure_lang(&mut lang, &cfg, &mut status);
+
+        if !cfg.test.skip_word_key.is_empty() && parse_key(&cfg.test.skip_word_key).is_none() {
+            status.push(
+                Priority::Warn,
+                format!(
+                    "Unknown skip word key `{}`, word skipping is disabled.",
+                    cfg.test.skip_word_key
+                ),
+                TimeDelta::seconds(5),
+            );
+        }
+
+        Self::unbind_invalid_keys(&mut cfg, &mut status);
+
+        let practice = Practice::load().unwrap_or_else(|e| {
+            status.push(
+                Priority::Error,
+                format!("Error reading practice queue: {e}"),
+                TimeDelta::seconds(5),
+            );
+            Practice::default()
+        });
+        let nemesis = Nemesis::load().unwrap_or_else(|e| {
+            status.push(
+                Priority::Error,
+                format!("Error reading missed words: {e}"),
+                TimeDelta::seconds(5),
+            );
+            Nemesis::default()
+        });
+
+        let goal = Goal::load().unwrap_or_else(|e| {
+            status.push(
+                Priority::Error,
+                format!("Error reading goal: {e}"),
+                TimeDelta::seconds(5),
+            );
+            None
+        });
+
+        let streak = Streak::load().unwrap_or_else(|e| {
+            status.push(
+                Priority::Error,
+                format!("Error reading streak: {e}"),
+                TimeDelta::seconds(5),
+            );
+            Streak::default()
+        });
+        let days = streak.current(Local::now().date_naive());
+        if days > 0 {
+            status.push(
+                Priority::Info,
+                format!(
+                    "Practice streak: {days} day{}.",
+                    if days == 1 { "" } else { "s" }
+                ),
+                TimeDelta::seconds(3),
+            );
+        }
+
+        let recovery = Recovery::load().unwrap_or_else(|e| {
+            status.push(
+                Priority::Error,
+                format!("Error reading interrupted test: {e}"),
+                TimeDelta::seconds(5),
+            );
+            None
+        });
+        if let Some(r) = recovery.as_ref().filter(|r| !r.continues(&lang)) {
+            status.push(
+                Priority::Info,
+                format!(
+                    "Found a test interrupted {}, press <{}> to resume it or <{}> to discard it.",
+                    r.saved.format("%Y-%m-%d %H:%M"),
+                    cfg.keybinds.resume,
+                    cfg.keybinds.discard_recovery
+                ),
+                TimeDelta::seconds(10),
+            );
+        }
+
+        for w in cfg.results.widgets.iter() {
+            if w.parse::<ResultsWidget>().is_err() {
+                status.push(
+                    Priority::Warn,
+                    format!(
+                        "Unknown results widget `{w}`, expected one of: {}.",
+                        ResultsWidget::VARIANTS.join(", ")
+                    ),
+                    TimeDelta::seconds(5),
+                );
+            }
+        }
+
+        for c in cfg.theme.invalid.iter() {
+            status.push(
+                Priority::Warn,
+                format!("Invalid theme color {c}, using the default."),
+                TimeDelta::seconds(5),
+            );
+        }
+
+        Self::check_contrast(&cfg, &mut status);
+
+        let styles = Styles::new(&cfg);
+        let (tx, rx) = sync_channel::<UiRequest>(REQUEST_QUEUE);
+        let test = Self::test_builder(&cfg, &styles, &tx)
+            .punctuated(lang.punctuated)
+            .endless(cfg.test.zen)
+            .build();
+        Self {
+            styles,
+            test,
+            seed: 0,
+            mode: Mode::default(),
+            history: Vec::new(),
+            stats_label: None,
+            review_deleted: false,
+            show_wpm_defs: false,
+            theme_i: 0,
+            completed_tests: 0,
+            recorded: false,
+            warmup: false,
+            last_input: Instant::now(),
+            idling: false,
+            results_shown: None,
+            screen_changed: Instant::now(),
+            clock: Local::now(),
+            deferred: VecDeque::new(),
             state: State::default(),
             screen: Screen::default(),
             last_screen: Screen::default(),
-            status: "Welcome to arstyper! Press <F1> for help, or 'Ctrl+C' to exit.".to_string(),
-            clear_status_at: Local::now() + TimeDelta::seconds(5),
-            cfg: cfg,
-            lang: lang,
+            status,
+            cfg,
+            lang,
+            custom_words,
+            practice,
+            nemesis,
+            goal,
+            streak,
+            recovery,
+            autosaved: None,
+            calibration: None,
             uireq_tx: tx,
             uireq_rx: rx,
-        })
+        }
     }
 
-    pub fn run(mut self) -> std::io::Result<()> {
-        let mut terminal = ratatui::init();
+    /// Unbind keys that can't be used, warning about them
+    fn unbind_invalid_keys(cfg: &mut Config, status: &mut StatusBar) {
+        let unbound = cfg.keybinds.unbind_invalid();
+        if !unbound.is_empty() {
+            status.push(
+                Priority::Warn,
+                format!("Invalid keys for {}, they are unbound.", unbound.join(", ")),
+                TimeDelta::seconds(5),
+            );
+        }
+    }
+
+    /// Apply the word options of `cfg` to `lang`, warning about any it can't follow
+    fn configure_lang(lang: &mut Lang, cfg: &Config, status: &mut StatusBar) {
+        if lang.invalid_lines > 0 {
+            status.push(
+                Priority::Warn,
+                format!(
+                    "Skipped {} invalid UTF-8 line{} in {}, see --lint.",
+                    lang.invalid_lines,
+                    if lang.invalid_lines == 1 { "" } else { "s" },
+                    lang.name
+                ),
+                TimeDelta::seconds(5),
+            );
+        }
+        if !lang.set_top(cfg.test.top_words) {
+            status.push(
+                Priority::Warn,
+                format!(
+                    "{} only has {} words, testing all of them.",
+                    lang.name,
+                    lang.words.len()
+                ),
+                TimeDelta::seconds(5),
+            );
+        }
+        lang.set_difficulty(cfg.test.difficulty);
+        if !lang.set_exclude(&cfg.test.exclude_chars) {
+            status.push(
+                Priority::Warn,
+                format!(
+                    "Every word in {} contains an excluded character, testing all of them.",
+                    lang.name
+                ),
+                TimeDelta::seconds(5),
+            );
+        }
+    }
+
+    /// Warn about theme colors that are hard to read, if `ui.check_contrast` is set
+    fn check_contrast(cfg: &Config, status: &mut StatusBar) {
+        if !cfg.ui.check_contrast {
+            return;
+        }
+        let low = cfg.theme.low_contrast();
+        if !low.is_empty() {
+            status.push(
+                Priority::Warn,
+                format!(
+                    "Theme colors {} may be hard to read on the background.",
+                    low.join(", ")
+                ),
+                TimeDelta::seconds(5),
+            );
+        }
+    }
 
+    fn test_builder(cfg: &Config, styles: &Styles, tx: &SyncSender<UiRequest>) -> TestBuilder {
+        TestBuilder::new(styles.clone(), tx.clone())
+            .cfg(cfg.test.clone())
+            .quiet_errors(cfg.ui.quiet_errors)
+            .highlight_correct(cfg.ui.highlight_correct)
+            // a page of one line is the focused line
+            .lines_per_page(if cfg.ui.focus_line {
+                1
+            } else {
+                cfg.ui.lines_per_page
+            })
+            .show_kps(cfg.ui.show_kps)
+            .chunk_size(cfg.ui.chunk_size)
+            .keybinds(cfg.keybinds.clone())
+            .max_width(cfg.ui.max_test_width, cfg.ui.test_alignment)
+    }
+
+    /// Take over the terminal for the TUI
+    fn enter_terminal(&self) -> std::io::Result<DefaultTerminal> {
+        let terminal = ratatui::init();
         let mut stdout = stdout();
         execute!(
             stdout,
             PushKeyboardEnhancementFlags(KeyboardEnhancementFlags::DISAMBIGUATE_ESCAPE_CODES)
         )?;
+        if self.cfg.test.pause_on_blur {
+            execute!(stdout, EnableFocusChange)?;
+        }
+        Ok(terminal)
+    }
 
-        self.test
-            .test_from(self.lang.gen_words(self.cfg.word_count as usize));
-        self.test
-            .set_title(format!("{} {}", self.lang.name, self.cfg.word_count).to_string()); // TODO use enum and strum and other things when more test types introduced
+    /// Give the terminal back, undoing [`Self::enter_terminal`]
+    fn leave_terminal(&self) -> std::io::Result<()> {
+        let mut stdout = stdout();
+        execute!(stdout, PopKeyboardEnhancementFlags)?;
+        if self.cfg.test.pause_on_blur {
+            execute!(stdout, DisableFocusChange)?;
+        }
+        ratatui::restore();
+        Ok(())
+    }
+
+    pub fn run(mut self) -> std::io::Result<()> {
+        let mut terminal = self.enter_terminal()?;
+
+        // an inorder test left part way continues where it was, down to the word in progress
+        if self
+            .recovery
+            .as_ref()
+            .is_some_and(|r| r.continues(&self.lang))
+        {
+            self.resume_recovery();
+        } else {
+            self.new_test();
+        }
         while self.state != State::Stopped {
             terminal.draw(|frame| frame.render_widget(&self, frame.area()))?;
             self.handle_events()?;
 
             // non-event-driven state logic
-            let t = Local::now();
-            if t >= self.clear_status_at {
-                self.clear_status();
+            self.status.tick();
+            if self.cfg.ui.idle_stats > 0
+                && !self.idling
+                && matches!(self.screen, Screen::Test)
+                && self.last_input.elapsed().as_secs() >= self.cfg.ui.idle_stats
+            {
+                self.start_idling();
             }
+            let dwell = self.cfg.test.results_dwell_secs;
+            if dwell > 0
+                && self
+                    .results_shown
+                    .is_some_and(|t| t.elapsed().as_secs() >= dwell)
+            {
+                self.results_shown = None;
+                let _ = self.uireq_tx.try_send(UiRequest::NewTest);
+            }
+            self.autosave();
+            self.feed_zen();
 
-            // message handling
-            if let Ok(msg) = self.uireq_rx.try_recv() {
+            // message handling, automatic screen changes wait until the current screen has been up long enough
+            self.receive_requests();
+            let wait = matches!(
+                self.deferred.front(),
+                Some(UiRequest::ChangeScreen(_) | UiRequest::NewTest)
+            ) && !self.min_screen_time_left().is_zero();
+            if !wait && let Some(msg) = self.deferred.pop_front() {
                 match msg {
-                    UiRequest::ChangeScreen(s) => self.screen = s,
+                    UiRequest::ChangeScreen(s) => {
+                        if matches!(s, Screen::Results) && self.test.is_completed() {
+                            self.record_test();
+                        }
+                        if matches!(s, Screen::Results) && self.calibration.is_some() {
+                            self.continue_calibration();
+                        } else {
+                            self.change_screen(s)
+                        }
+                    }
+                    UiRequest::NewTest => {
+                        self.new_test();
+                        self.change_screen(Screen::Test);
+                    }
+                    UiRequest::EditConfig => {
+                        self.leave_terminal()?;
+                        let edited = self.edit_config();
+                        terminal = self.enter_terminal()?;
+                        terminal.clear()?;
+                        match edited {
+                            Ok(()) => self.reload_config(),
+                            Err(e) => self.status.push(
+                                Priority::Error,
+                                format!("Error running editor: {e}"),
+                                TimeDelta::seconds(5),
+                            ),
+                        }
+                    }
+                    UiRequest::DisplayStatus(pri, msg, dur) => self.status.push(pri, msg, dur),
                 }
             }
         }
 
-        execute!(stdout, PopKeyboardEnhancementFlags)?;
-        ratatui::restore();
+        // a clean exit leaves nothing to recover
+        self.clear_recovery();
+        if self.cfg.ui.set_terminal_title {
+            execute!(stdout(), SetTitle(""))?;
+        }
+        self.leave_terminal()
+    }
+
+    /// Take every request sent since the last tick. Statuses leave the screen alone, so are shown
+    /// straight away, others are queued to be handled one per tick
+    fn receive_requests(&mut self) {
+        while let Ok(msg) = self.uireq_rx.try_recv() {
+            match msg {
+                UiRequest::DisplayStatus(pri, msg, dur) => self.status.push(pri, msg, dur),
+                // every key on a frozen test asks for results, which are only shown once
+                UiRequest::ChangeScreen(Screen::Results)
+                    if self
+                        .deferred
+                        .iter()
+                        .any(|m| matches!(m, UiRequest::ChangeScreen(Screen::Results))) => {}
+                msg => self.deferred.push_back(msg),
+            }
+        }
+    }
 
+    /// Open the config file in `$VISUAL` or `$EDITOR`, waiting for it to exit.
+    /// The terminal must be given back first.
+    fn edit_config(&self) -> std::io::Result<()> {
+        let editor = std::env::var("VISUAL")
+            .or_else(|_| std::env::var("EDITOR"))
+            .unwrap_or_else(|_| if cfg!(windows) { "notepad" } else { "vi" }.to_string());
+        let status = Command::new(&editor).arg(Config::path()).status()?;
+        if !status.success() {
+            return Err(std::io::Error::other(format!(
+                "{editor} exited with {status}"
+            )));
+        }
         Ok(())
     }
 
-    fn handle_events(&mut self) -> std::io::Result<()> {
-        if poll(std::time::Duration::from_secs(1))?
-            && let Event::Key(key) = event::read()?
-        {
-            if key.kind == KeyEventKind::Press {
-                // global keys
-                match key.code {
-                    KeyCode::Char('c') => {
-                        if key.modifiers.contains(KeyModifiers::CONTROL) {
-                            self.state = State::Stopped
+    /// Re-read the config file, restyling and starting a new test
+    fn reload_config(&mut self) {
+        match self.cfg.reload() {
+            Ok(cfg) => {
+                self.cfg = cfg;
+                Self::unbind_invalid_keys(&mut self.cfg, &mut self.status);
+                // custom words have no language to switch from
+                if self.custom_words.is_none() && self.cfg.lang != self.lang.name {
+                    match Lang::get_by_name(&self.cfg.lang, &self.cfg.lang_dirs()) {
+                        Ok(lang) => self.lang = lang,
+                        Err(e) => {
+                            self.status.push(
+                                Priority::Error,
+                                format!("Error loading {}: {e}", self.cfg.lang),
+                                TimeDelta::seconds(5),
+                            );
+                            self.cfg.lang = self.lang.name.clone();
                         }
                     }
-                    KeyCode::F(1) => {
-                        self.set_status_for(
-                            "Press <ESC> or 'q' to go back.".to_string(),
+                }
+                Self::configure_lang(&mut self.lang, &self.cfg, &mut self.status);
+                self.styles = Styles::new(&self.cfg);
+                self.test = Self::test_builder(&self.cfg, &self.styles, &self.uireq_tx).build();
+                self.new_test();
+                self.change_screen(Screen::Test);
+                self.status.push(
+                    Priority::Info,
+                    "Reloaded config.".to_string(),
+                    TimeDelta::seconds(3),
+                );
+            }
+            Err(e) => self.status.push(
+                Priority::Error,
+                format!("Error reloading config: {e}"),
+                TimeDelta::seconds(5),
+            ),
+        }
+    }
+
+    /// Re-read only the theme from the config file, restyling the test in progress.
+    /// The current theme is kept if the file can't be read
+    fn reload_theme(&mut self) {
+        match self.cfg.reload() {
+            // a typo mustn't swap colors out for the defaults mid-session
+            Ok(cfg) if !cfg.theme.invalid.is_empty() => {
+                for c in cfg.theme.invalid.iter() {
+                    self.status.push(
+                        Priority::Warn,
+                        format!("Invalid theme color {c}, keeping the current theme."),
+                        TimeDelta::seconds(5),
+                    );
+                }
+            }
+            Ok(cfg) => {
+                self.cfg.theme = cfg.theme;
+                self.styles = Styles::new(&self.cfg);
+                self.test.set_styles(self.styles.clone());
+                self.status.push(
+                    Priority::Info,
+                    "Reloaded theme.".to_string(),
+                    TimeDelta::seconds(3),
+                );
+                Self::check_contrast(&self.cfg, &mut self.status);
+            }
+            Err(e) => self.status.push(
+                Priority::Error,
+                format!("Error reloading theme, keeping the current one: {e}"),
+                TimeDelta::seconds(5),
+            ),
+        }
+    }
+
+    /// Time left until the current screen has been shown for `ui.min_screen_ms`
+    fn min_screen_time_left(&self) -> Duration {
+        Duration::from_millis(self.cfg.ui.min_screen_ms)
+            .saturating_sub(self.screen_changed.elapsed())
+    }
+
+    fn handle_events(&mut self) -> std::io::Result<()> {
+        // wake up in time for deferred requests
+        let mut timeout = match self.deferred.front() {
+            Some(_) => self.min_screen_time_left(),
+            None => Duration::from_secs(1),
+        };
+        // and for the metronome to change
+        if let Some((_, next)) = self.metronome_state() {
+            timeout = timeout.min(next);
+        }
+        // and for obscured words to be hidden
+        if let Some(left) = self.test.reveal_left() {
+            timeout = timeout.min(left);
+        }
+        // and for the next frame of an animation
+        if self.results_reveal().is_some() {
+            timeout = timeout.min(ANIMATION_FRAME);
+        }
+        if poll(timeout)? {
+            self.clock = Local::now();
+            match event::read()? {
+                Event::Key(key) if key.kind == KeyEventKind::Press => self.handle_key(key),
+                Event::FocusLost if self.cfg.test.pause_on_blur => self.test.pause(),
+                Event::FocusGained if self.cfg.test.pause_on_blur => self.test.resume(),
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    fn handle_key(&mut self, key: KeyEvent) {
+        self.last_input = Instant::now();
+        // any key dismisses idle statistics
+        if self.idling {
+            self.idling = false;
+            return;
+        }
+
+        // global keys
+        let kb = self.cfg.keybinds.clone();
+        let bound = |spec: &str| key_matches(spec, &key);
+        match key.code {
+            _ if bound(&kb.quit) => {
+                self.keep_inorder_place(false);
+                self.state = State::Stopped;
+            }
+            // save and quit, recording the test in progress
+            _ if bound(&kb.save_quit) => {
+                self.save_and_quit();
+                return;
+            }
+            // word count presets
+            _ if let Some(i) = kb.presets.iter().position(|p| bound(p)) => {
+                let wc = WORD_COUNT_PRESETS[i];
+                self.cfg.word_count = wc;
+                self.status.push(
+                    Priority::Info,
+                    format!("Word count set to {wc}."),
+                    TimeDelta::seconds(3),
+                );
+                // titled with the new count
+                self.new_test();
+                self.change_screen(Screen::Test);
+                return;
+            }
+            _ if bound(&kb.caret) => {
+                let caret = self.cfg.ui.caret.next();
+                self.cfg.ui.caret = caret;
+                self.styles.cursor = caret.style(self.styles.root, self.cfg.theme.accent);
+                self.test.set_styles(self.styles.clone());
+                self.status.push(
+                    Priority::Info,
+                    format!("Caret: {caret}"),
+                    TimeDelta::seconds(2),
+                );
+            }
+            _ if bound(&kb.edit_config) => {
+                let _ = self.uireq_tx.try_send(UiRequest::EditConfig);
+            }
+            _ if bound(&kb.themes) => self.change_screen(Screen::Theme),
+            _ if bound(&kb.calibrate) => {
+                self.start_calibration();
+                return;
+            }
+            _ if bound(&kb.next_favorite) => {
+                self.next_favorite();
+                return;
+            }
+            _ if bound(&kb.statistics) => self.change_screen(Screen::Statistics),
+            _ if bound(&kb.resume) => {
+                self.resume_recovery();
+                return;
+            }
+            _ if bound(&kb.discard_recovery) => self.discard_recovery(),
+            _ if bound(&kb.reload_theme) => self.reload_theme(),
+            // cycle screens, except out of calibration. On the test screen the key is left to the test,
+            // where it ends it by default, which moves on to results all the same
+            _ if bound(&kb.next_screen)
+                && !matches!(self.screen, Screen::Test | Screen::Calibration) =>
+            {
+                self.cycle_screen(true);
+                return;
+            }
+            _ if bound(&kb.previous_screen) && !matches!(self.screen, Screen::Calibration) => {
+                self.cycle_screen(false);
+                return;
+            }
+            // abort calibration, keeping the test. Keys that type are left to the test
+            _ if bound(&kb.back)
+                && !types_text(key.code, key.modifiers)
+                && matches!(self.screen, Screen::Test)
+                && self.calibration.is_some() =>
+            {
+                self.calibration = None;
+                self.status.push(
+                    Priority::Info,
+                    "Calibration aborted.".to_string(),
+                    TimeDelta::seconds(3),
+                );
+                return;
+            }
+            _ if bound(&kb.help) => {
+                self.status.push(
+                    Priority::Info,
+                    format!("Press {} to go back.", key_names(&kb.back)),
+                    TimeDelta::seconds(3),
+                );
+                self.change_screen(Screen::About)
+            }
+            _ => {}
+        }
+
+        // per-screen keys
+        match self.screen {
+            Screen::About => self.handle_about_events(key),
+            Screen::Test => self.test.handle_events(key),
+            Screen::Results => self.handle_results_events(key),
+            Screen::Statistics => self.handle_statistics_events(key),
+            Screen::Theme => self.handle_theme_events(key),
+            Screen::Calibration => self.handle_calibration_events(key),
+        }
+    }
+
+    /// Move to the next or previous screen of those cycled through with Tab
+    fn cycle_screen(&mut self, forward: bool) {
+        let s = self.screen.cycle(forward);
+        // a finished test has nothing left to show
+        if matches!(s, Screen::Test) && self.test.is_completed() {
+            self.new_test();
+        }
+        self.change_screen(s);
+    }
+
+    /// Discard the current test and generate a new one
+    fn new_test(&mut self) {
+        // a test left frozen on completion never went to results, so is recorded before it's replaced
+        if self.test.is_completed() {
+            self.record_test();
+        }
+        self.recorded = false;
+        self.clear_recovery();
+        self.warmup = false;
+        // replay the seed from the CLI once, random afterwards
+        self.seed = self.cfg.seed.take().unwrap_or_else(rand::random);
+        self.test.set_seed(self.seed);
+        let n = self.cfg.word_count as usize;
+        let warmup = self
+            .lang
+            .sample(self.cfg.test.warmup_words, !self.seed)
+            .collect::<Vec<String>>()
+            .into_iter();
+        self.test.set_endless(false);
+        let name = match &self.custom_words {
+            Some(w) => {
+                self.mode = Mode::Custom;
+                self.test.set_punctuated(false);
+                self.test.test_with_warmup(warmup, w.iter().cloned());
+                self.lang.name.clone()
+            }
+            // calibration needs tests that end
+            None if self.cfg.test.zen && self.calibration.is_none() => {
+                self.mode = Mode::Zen;
+                self.test.set_punctuated(self.lang.punctuated);
+                self.test.set_endless(true);
+                self.test
+                    .test_with_warmup(warmup, self.lang.sample(ZEN_BUFFER, self.seed));
+                self.lang.name.clone()
+            }
+            None => {
+                let (mode, missed) = if self.cfg.test.nemesis {
+                    (Mode::Nemesis, self.nemesis.gen_words(n, self.seed))
+                } else if self.cfg.test.practice {
+                    (Mode::Practice, self.practice.gen_words(n, self.seed))
+                } else {
+                    (Mode::Words, Vec::new())
+                };
+                if !missed.is_empty() {
+                    self.mode = mode;
+                    self.test.set_punctuated(false);
+                    self.test.test_with_warmup(warmup, missed.into_iter());
+                    mode.to_string()
+                } else {
+                    let empty = match mode {
+                        Mode::Nemesis => "No missed words recorded yet",
+                        Mode::Practice => "Practice queue is empty",
+                        _ => "",
+                    };
+                    if !empty.is_empty() {
+                        self.status.push(
+                            Priority::Info,
+                            format!("{empty}, testing {}.", self.lang.name),
                             TimeDelta::seconds(3),
                         );
-                        self.change_screen(Screen::AboutScreen)
                     }
-                    _ => {}
+                    self.mode = Mode::Words;
+                    self.test.set_punctuated(self.lang.punctuated);
+                    self.test
+                        .test_with_warmup(warmup, self.lang.gen_words(n, self.seed));
+                    self.lang.name.clone()
                 }
+            }
+        };
+        let fav = if self.cfg.favorites.contains(&name) {
+            "★ "
+        } else {
+            ""
+        };
+        let title = if self.test.is_endless() {
+            format!("{fav}{name} {}", Mode::Zen)
+        } else {
+            format!("{fav}{name} {}", self.test.word_count())
+        }; // TODO use enum and strum and other things when more test types introduced
+        if self.cfg.ui.set_terminal_title {
+            let _ = execute!(stdout(), SetTitle(format!("arstyper — {title}")));
+        }
+        self.test.set_title(title);
+    }
 
-                // per-screen keys
-                match self.screen {
-                    Screen::AboutScreen => self.handle_about_events(key),
-                    Screen::TestScreen => self.test.handle_events(key),
-                    _ => {}
-                }
+    /// Switch to the favorite language after the current one, or the first if it isn't a favorite
+    fn next_favorite(&mut self) {
+        let favs = &self.cfg.favorites;
+        if favs.is_empty() {
+            self.status.push(
+                Priority::Info,
+                "No favorite languages, add some to `favorites` in the config.".to_string(),
+                TimeDelta::seconds(3),
+            );
+            return;
+        }
+        let next = match favs.iter().position(|f| *f == self.lang.name) {
+            Some(i) => &favs[(i + 1) % favs.len()],
+            None => &favs[0],
+        };
+        match Lang::get_by_name(next, &self.cfg.lang_dirs()) {
+            Ok(mut lang) => {
+                Self::configure_lang(&mut lang, &self.cfg, &mut self.status);
+                self.cfg.lang = lang.name.clone();
+                self.lang = lang;
+                self.new_test();
+                self.change_screen(Screen::Test);
+            }
+            Err(e) => self.status.push(
+                Priority::Error,
+                format!("Error loading {next}: {e}"),
+                TimeDelta::seconds(5),
+            ),
+        }
+    }
+
+    /// Start chaining `stats.calibration_tests` tests to measure a baseline speed
+    fn start_calibration(&mut self) {
+        let c = Calibration::new(self.cfg.stats.calibration_tests);
+        self.calibration = Some(c);
+        self.new_test();
+        self.change_screen(Screen::Test);
+        self.push_calibration_progress();
+    }
+
+    fn push_calibration_progress(&mut self) {
+        if let Some(c) = &self.calibration {
+            let (i, n) = c.progress();
+            self.status.push(
+                Priority::Info,
+                format!(
+                    "Calibration test {i}/{n}. Press {} to abort.",
+                    key_names(&self.cfg.keybinds.back)
+                ),
+                TimeDelta::seconds(3),
+            );
+        }
+    }
+
+    /// Count the test just ended towards calibration, then start the next or show the baseline.
+    /// Tests ended early abort calibration instead
+    fn continue_calibration(&mut self) {
+        let Some(c) = &mut self.calibration else {
+            return;
+        };
+        if !self.test.is_completed() || self.test.is_aborted() {
+            self.calibration = None;
+            self.status.push(
+                Priority::Info,
+                "Calibration aborted, test ended early.".to_string(),
+                TimeDelta::seconds(3),
+            );
+            self.change_screen(Screen::Results);
+            return;
+        }
+        c.push(self.test.metrics());
+        if c.is_done() {
+            self.change_screen(Screen::Calibration);
+        } else {
+            self.new_test();
+            self.push_calibration_progress();
+        }
+    }
+
+    /// Record a started test that hasn't been yet, even if incomplete, then stop
+    fn save_and_quit(&mut self) {
+        // tests are recorded on the way to the results screen, so only ones still on screen are unrecorded
+        // zen tests have no end to record
+        if matches!(self.screen, Screen::Test) && self.test.is_started() && !self.test.is_endless()
+        {
+            self.record_partial();
+            self.keep_inorder_place(true);
+        }
+        self.state = State::Stopped;
+    }
+
+    /// Save the place in an unfinished `inorder` test, down to the keys of the word in progress, so
+    /// the next session continues there. `recorded` if its typed part was just added to history
+    fn keep_inorder_place(&mut self, recorded: bool) {
+        // the snapshot of an earlier session is kept until it is resumed or discarded
+        if !self.lang.inorder
+            || self.mode != Mode::Words
+            || self.calibration.is_some()
+            || self.recovery.is_some()
+            || !matches!(self.screen, Screen::Test)
+            || !self.test.is_started()
+            || self.test.is_completed()
+            || self.test.is_endless()
+        {
+            return;
+        }
+        let r = Recovery {
+            recorded,
+            ..self.snapshot()
+        };
+        // no longer an autosave, so the clean exit doesn't remove it
+        self.autosaved = None;
+        if let Err(e) = r.save() {
+            self.status.push(
+                Priority::Error,
+                format!("Error saving position in {}: {e}", self.lang.name),
+                TimeDelta::seconds(5),
+            );
+        }
+    }
+
+    /// Append the test quit part way through to history, as only the words typed so far.
+    /// It is left out of the streak, practice queue and missed words, which count completed tests.
+    fn record_partial(&mut self) {
+        self.clear_recovery();
+        if self.mode == Mode::Words {
+            self.advance_inorder(self.test.progress().0);
+        }
+        if let Err(e) = results::append(&self.partial_record(), self.cfg.stats.max_history) {
+            self.status.push(
+                Priority::Error,
+                format!("Error saving result to history: {e}"),
+                TimeDelta::seconds(5),
+            );
+        }
+    }
+
+    /// Result of the current test as it stands
+    fn test_record(&self) -> Record {
+        Record {
+            timestamp: Local::now(),
+            lang: self.lang.name.clone(),
+            label: self.cfg.label.clone(),
+            word_count: self.test.word_count(),
+            correct_chars: self.test.correct_chars(),
+            secs: self.test.elapsed_secs(),
+            accuracy: Some(self.test.accuracy()),
+            mode: self.mode,
+            partial: false,
+        }
+    }
+
+    /// Result of the words typed so far of a test that hasn't been completed
+    fn partial_record(&self) -> Record {
+        Record {
+            word_count: self.test.progress().0,
+            partial: true,
+            ..self.test_record()
+        }
+    }
+
+    /// Snapshot the test in progress every `stats.autosave_secs`
+    fn autosave(&mut self) {
+        let secs = self.cfg.stats.autosave_secs;
+        // counting from the first keypress, so tests that were never started aren't saved
+        let due = self
+            .autosaved
+            .or(self.test.started_at())
+            .is_some_and(|t| t.elapsed().as_secs() >= secs);
+        // the snapshot of an earlier session is kept until it is resumed or discarded
+        if secs == 0
+            || !due
+            || self.recovery.is_some()
+            || !matches!(self.screen, Screen::Test)
+            || self.test.is_completed()
+            || self.test.is_endless()
+        {
+            return;
+        }
+        let r = self.snapshot();
+        self.autosaved = Some(Instant::now());
+        if let Err(e) = r.save() {
+            self.status.push(
+                Priority::Error,
+                format!("Error autosaving test: {e}"),
+                TimeDelta::seconds(5),
+            );
+        }
+    }
+
+    /// The test in progress from the word being typed on, to pick it up again later
+    fn snapshot(&self) -> Recovery {
+        Recovery {
+            saved: Local::now(),
+            record: self.partial_record(),
+            words: self.test.remaining_words(),
+            punctuated: self.test.is_punctuated(),
+            partial: self.test.partial_keys(),
+            recorded: false,
+        }
+    }
+
+    /// Queue more words for a zen test running low
+    fn feed_zen(&mut self) {
+        if !self.test.is_endless() || self.test.words_left() >= ZEN_BUFFER / 2 {
+            return;
+        }
+        // zen tests are never replayed, so the seed doesn't matter
+        self.test
+            .extend(self.lang.sample(ZEN_BUFFER / 2, rand::random()));
+    }
+
+    /// Remove the autosave of the current test, if there is one
+    fn clear_recovery(&mut self) {
+        if self.autosaved.take().is_some()
+            && let Err(e) = Recovery::clear()
+        {
+            self.status.push(
+                Priority::Error,
+                format!("Error removing autosaved test: {e}"),
+                TimeDelta::seconds(5),
+            );
+        }
+    }
+
+    /// Remove the snapshot of a test interrupted in an earlier session without resuming it
+    fn discard_recovery(&mut self) {
+        if self.recovery.take().is_none() {
+            self.status.push(
+                Priority::Info,
+                "No interrupted test to discard.".to_string(),
+                TimeDelta::seconds(3),
+            );
+            return;
+        }
+        match Recovery::clear() {
+            Ok(()) => self.status.push(
+                Priority::Info,
+                "Discarded interrupted test.".to_string(),
+                TimeDelta::seconds(3),
+            ),
+            Err(e) => self.status.push(
+                Priority::Error,
+                format!("Error removing interrupted test: {e}"),
+                TimeDelta::seconds(5),
+            ),
+        }
+    }
+
+    /// Record the typed part of the interrupted test and continue with the rest of its words
+    fn resume_recovery(&mut self) {
+        let Some(r) = self.recovery.take() else {
+            self.status.push(
+                Priority::Info,
+                "No interrupted test to resume.".to_string(),
+                TimeDelta::seconds(3),
+            );
+            return;
+        };
+        // its typed part moves an inorder language on as it's recorded
+        if !r.recorded && r.continues(&self.lang) {
+            self.advance_inorder(r.record.word_count);
+        }
+        if !r.recorded
+            && r.record.secs > 0.0
+            && let Err(e) = results::append(&r.record, self.cfg.stats.max_history)
+        {
+            self.status.push(
+                Priority::Error,
+                format!("Error saving result to history: {e}"),
+                TimeDelta::seconds(5),
+            );
+        }
+        // recorded, so it mustn't be offered again
+        self.autosaved = Some(Instant::now());
+        self.clear_recovery();
+        self.recorded = false;
+
+        self.test.set_punctuated(r.punctuated);
+        self.test
+            .test_with_warmup(std::iter::empty(), r.words.into_iter());
+        if !r.partial.is_empty() && !self.test.replay_word(&r.partial) {
+            self.status.push(
+                Priority::Warn,
+                "Couldn't restore the word in progress, it starts over.".to_string(),
+                TimeDelta::seconds(5),
+            );
+        }
+        self.test.set_title(format!(
+            "{} {} (resumed)",
+            r.record.lang,
+            self.test.word_count()
+        ));
+        self.change_screen(Screen::Test);
+    }
+
+    /// Move an `inorder` language on past `n` typed words and save its place, so the next test
+    /// continues after them
+    fn advance_inorder(&mut self, n: usize) {
+        if !self.lang.inorder {
+            return;
+        }
+        self.lang.advance_inorder(n);
+        if let Err(e) = self.lang.save_inorder_index() {
+            self.status.push(
+                Priority::Error,
+                format!("Error saving position in {}: {e}", self.lang.name),
+                TimeDelta::seconds(5),
+            );
+        }
+    }
+
+    /// Append the completed test to history, once
+    fn record_test(&mut self) {
+        if self.recorded {
+            return;
+        }
+        self.recorded = true;
+        self.completed_tests += 1;
+        if self.mode == Mode::Words {
+            self.advance_inorder(self.test.progress().0);
+        }
+        self.clear_recovery();
+        if self.streak.record(Local::now().date_naive())
+            && let Err(e) = self.streak.save()
+        {
+            self.status.push(
+                Priority::Error,
+                format!("Error saving streak: {e}"),
+                TimeDelta::seconds(5),
+            );
+        }
+        if self.cfg.stats.skip_warmup && self.completed_tests == 1 {
+            self.warmup = true;
+            return;
+        }
+
+        let r = self.test_record();
+        if let Err(e) = results::append(&r, self.cfg.stats.max_history) {
+            self.status.push(
+                Priority::Error,
+                format!("Error saving result to history: {e}"),
+                TimeDelta::seconds(5),
+            );
+        }
+
+        // reversed and custom words aren't words of the language worth practicing
+        if !self.cfg.test.reverse && self.custom_words.is_none() {
+            self.practice.record(self.test.word_results());
+            if let Err(e) = self.practice.save() {
+                self.status.push(
+                    Priority::Error,
+                    format!("Error saving practice queue: {e}"),
+                    TimeDelta::seconds(5),
+                );
+            }
+            self.nemesis.record(self.test.word_results());
+            if let Err(e) = self.nemesis.save() {
+                self.status.push(
+                    Priority::Error,
+                    format!("Error saving missed words: {e}"),
+                    TimeDelta::seconds(5),
+                );
             }
         }
-        Ok(())
     }
 
     fn render_results(&self, area: Rect, buf: &mut Buffer) {
-        Paragraph::new("res").render(area, buf);
+        let widgets = self
+            .cfg
+            .results
+            .widgets
+            .iter()
+            .filter_map(|w| w.parse::<ResultsWidget>().ok())
+            .collect::<Vec<ResultsWidget>>();
+        let wpms = self.test.word_wpm();
+        let areas = Layout::vertical(widgets.iter().map(|w| match w {
+            ResultsWidget::Speed
+            | ResultsWidget::Burst
+            | ResultsWidget::Errors
+            | ResultsWidget::Hands => Constraint::Length(1),
+            ResultsWidget::Definitions if self.show_wpm_defs => Constraint::Length(6),
+            ResultsWidget::Definitions => Constraint::Length(0),
+            ResultsWidget::Timeline => Constraint::Length(5),
+            ResultsWidget::Histogram if wpms.len() < HISTOGRAM_MIN_WORDS => Constraint::Length(2),
+            ResultsWidget::Histogram => Constraint::Length(8),
+            ResultsWidget::Review => Constraint::Min(0),
+        }))
+        .split(area);
+
+        for (w, a) in widgets.iter().zip(areas.iter()) {
+            match w {
+                ResultsWidget::Speed => {
+                    self.render_speed(widgets.contains(&ResultsWidget::Definitions), *a, buf)
+                }
+                ResultsWidget::Burst => self.render_burst(*a, buf),
+                ResultsWidget::Errors => self.render_errors(*a, buf),
+                ResultsWidget::Hands => self.render_hands(*a, buf),
+                ResultsWidget::Definitions if self.show_wpm_defs => self.render_wpm_defs(*a, buf),
+                ResultsWidget::Definitions => {}
+                ResultsWidget::Timeline => self.render_timeline(*a, buf),
+                ResultsWidget::Histogram => self.render_histogram(&wpms, *a, buf),
+                ResultsWidget::Review => self.render_review(*a, buf),
+            }
+        }
+    }
+
+    /// Speed and notes about the test, with a hint for WPM definitions if they can be shown
+    fn render_speed(&self, defs_hint: bool, area: Rect, buf: &mut Buffer) {
+        let unit = self.cfg.stats.unit;
+        let mut header = vec![Span::raw(format!(
+            "Speed: {:.1} {unit}  Accuracy: {:.1}%",
+            unit.of_wpm(self.test.wpm()),
+            self.test.accuracy()
+        ))];
+        if let Some(g) = &self.goal {
+            header.push(Span::raw(format!(" (goal: {:.1})", unit.of_wpm(g.wpm))));
+        }
+        if self.test.is_aborted() {
+            header.push(Span::raw(" (aborted: accuracy)").style(self.styles.accent));
+        } else if self.warmup {
+            header.push(Span::raw(" (warmup, not recorded)").style(self.styles.accent));
+        }
+        if defs_hint {
+            header.push(Span::raw(if self.show_wpm_defs {
+                " ('w' to hide other definitions)"
+            } else {
+                " ('w' to compare other definitions)"
+            }));
+        }
+        Line::from(header).style(self.styles.root).render(area, buf);
+    }
+
+    fn render_burst(&self, area: Rect, buf: &mut Buffer) {
+        let burst = self
+            .test
+            .burst_percent(Duration::from_millis(self.cfg.stats.burst_ms));
+        Line::raw(match burst {
+            Some(b) => format!("Burst typing: {b:.0}%"),
+            None => "Burst typing: -".to_string(),
+        })
+        .style(self.styles.root)
+        .render(area, buf);
+    }
+
+    fn render_errors(&self, area: Rect, buf: &mut Buffer) {
+        let (corrected, uncorrected) = self.test.error_fates();
+        Line::raw(format!(
+            "Errors: {corrected} corrected, {uncorrected} uncorrected"
+        ))
+        .style(self.styles.root)
+        .render(area, buf);
+    }
+
+    fn render_hands(&self, area: Rect, buf: &mut Buffer) {
+        let ms = |h| match self.test.hand_interval(h) {
+            Some(ms) => format!("{ms:.0}ms"),
+            None => "-".to_string(),
+        };
+        Line::raw(format!(
+            "Hands: L: {} · R: {}",
+            ms(Hand::Left),
+            ms(Hand::Right)
+        ))
+        .style(self.styles.root)
+        .render(area, buf);
+    }
+
+    fn render_wpm_defs(&self, area: Rect, buf: &mut Buffer) {
+        let m = self.test.metrics();
+        Paragraph::new(
+            [
+                (
+                    m.net_wpm(),
+                    "net: correct characters and spaces / 5 (arstyper)",
+                ),
+                (m.no_space_wpm(), "correct characters without spaces / 5"),
+                (m.gross_wpm(), "gross: all typed characters / 5"),
+                (
+                    m.ansi_wpm(),
+                    "ANSI: gross less uncorrected errors per minute",
+                ),
+                (m.literal_wpm(), "correct words, of any length"),
+            ]
+            .map(|(w, def)| Line::raw(format!("{w:>6.1} WPM  {def}")))
+            .to_vec(),
+        )
+        .style(self.styles.root)
+        .block(
+            Block::new()
+                .borders(Borders::TOP)
+                .style(self.styles.accent)
+                .title("WPM definitions".bold())
+                .padding(Padding::horizontal(1)),
+        )
+        .render(area, buf);
+    }
+
+    fn render_timeline(&self, area: Rect, buf: &mut Buffer) {
+        let unit = self.cfg.stats.unit;
+        let speeds = self
+            .test
+            .wpm_over_time(TIMELINE_BUCKET)
+            .into_iter()
+            .map(|(_, w)| unit.of_wpm(w).round() as u64)
+            .collect::<Vec<u64>>();
+        let peak = speeds.iter().max().copied().unwrap_or(0);
+        let block = Block::new()
+            .borders(Borders::TOP)
+            .style(self.styles.accent)
+            .title(format!("{unit} over time (peak {peak})").bold())
+            .padding(Padding::horizontal(1));
+        Sparkline::default()
+            .data(&speeds)
+            .style(self.styles.accent)
+            .block(block)
+            .render(area, buf);
+    }
+
+    fn render_histogram(&self, wpms: &[f64], area: Rect, buf: &mut Buffer) {
+        let hist_block = Block::new()
+            .borders(Borders::TOP)
+            .style(self.styles.accent)
+            .title("Word WPM".bold())
+            .padding(Padding::horizontal(1));
+        if wpms.len() < HISTOGRAM_MIN_WORDS {
+            Paragraph::new(
+                wpms.iter()
+                    .map(|w| format!("{w:.0}"))
+                    .collect::<Vec<String>>()
+                    .join(" "),
+            )
+            .style(self.styles.root)
+            .block(hist_block)
+            .render(area, buf);
+        } else {
+            BarChart::new(
+                histogram(wpms)
+                    .into_iter()
+                    .map(|(l, n)| Bar::with_label(l, n))
+                    .collect::<Vec<Bar>>(),
+            )
+            .bar_width(4)
+            .bar_style(self.styles.accent)
+            .value_style(self.styles.modeline)
+            .label_style(self.styles.root)
+            .style(self.styles.root)
+            .block(hist_block)
+            .render(area, buf);
+        }
+    }
+
+    fn render_review(&self, area: Rect, buf: &mut Buffer) {
+        let kb = &self.cfg.keybinds;
+        let hint = format!(
+            "{} to {} deleted characters, {} to export keystrokes",
+            key_names(&kb.review_deleted),
+            if self.review_deleted { "hide" } else { "show" },
+            key_names(&kb.export_keystrokes)
+        );
+        Paragraph::new(self.test.review_line(self.review_deleted))
+            .style(self.styles.root)
+            .block(
+                Block::new()
+                    .borders(Borders::TOP)
+                    .style(self.styles.accent)
+                    .title("Review".bold())
+                    .title_bottom(hint)
+                    .title_bottom(Line::from(format!("seed {}", self.seed)).right_aligned())
+                    .padding(Padding::horizontal(1)),
+            )
+            .wrap(Wrap { trim: true })
+            .render(area, buf);
+    }
+
+    fn handle_results_events(&mut self, key: KeyEvent) {
+        self.results_shown = None;
+        let kb = &self.cfg.keybinds;
+        let bound = |spec: &str| key_matches(spec, &key);
+        match key.code {
+            _ if bound(&kb.review_deleted) => self.review_deleted = !self.review_deleted,
+            _ if bound(&kb.wpm_definitions) => self.show_wpm_defs = !self.show_wpm_defs,
+            _ if bound(&kb.export_keystrokes) => {
+                let p = results::dir().join(format!(
+                    "keystrokes-{}.jsonl",
+                    Local::now().format("%Y%m%d-%H%M%S")
+                ));
+                let written = fs::create_dir_all(results::dir())
+                    .and_then(|()| fs::write(&p, self.test.keystroke_log()));
+                let (pri, msg) = match written {
+                    Ok(()) => (
+                        Priority::Info,
+                        format!("Exported keystrokes to {}", p.display()),
+                    ),
+                    Err(e) => (Priority::Error, format!("Error exporting keystrokes: {e}")),
+                };
+                self.status.push(pri, msg, TimeDelta::seconds(5));
+            }
+            _ => {}
+        }
     }
 
     fn render_statistics(&self, area: Rect, buf: &mut Buffer) {
-        Paragraph::new("stats").render(area, buf);
+        let label = match &self.stats_label {
+            Some(l) => l.as_str(),
+            None => "<all>",
+        };
+        let [label_a, table_a] =
+            Layout::vertical([Constraint::Length(2), Constraint::Min(0)]).areas(area);
+        Line::from(vec![
+            Span::raw("Label: "),
+            Span::raw(label).style(self.styles.accent),
+            Span::raw(format!(
+                " ({}/{} to change, {} to export report)",
+                key_names(&self.cfg.keybinds.browse_previous),
+                key_names(&self.cfg.keybinds.browse_next),
+                key_names(&self.cfg.keybinds.export_report)
+            )),
+        ])
+        .style(self.styles.root)
+        .render(label_a, buf);
+
+        let unit = self.cfg.stats.unit;
+        let records = self.filtered_history().collect::<Vec<&Record>>();
+        let Some(s) = Summary::of(&records, unit) else {
+            Line::raw("No tests recorded yet.")
+                .style(self.styles.root)
+                .render(table_a, buf);
+            return;
+        };
+        let rows = [
+            ("Tests recorded", s.tests.to_string()),
+            ("Average speed", format!("{:.1} {unit}", s.avg_speed)),
+            (
+                "Best speed",
+                format!(
+                    "{} ({}, {})",
+                    unit.format(s.best.correct_chars, s.best.secs),
+                    s.best.lang,
+                    s.best.timestamp.format("%Y-%m-%d")
+                ),
+            ),
+            (
+                "Average accuracy",
+                s.avg_accuracy
+                    .map_or("-".to_string(), |a| format!("{a:.1}%")),
+            ),
+            (
+                "Practice streak",
+                format!("{} days", self.streak.current(Local::now().date_naive())),
+            ),
+        ]
+        .map(|(k, v)| Row::new([Cell::from(k), Cell::from(v).style(self.styles.accent)]));
+        Table::new(rows, [Constraint::Length(18), Constraint::Min(0)])
+            .style(self.styles.root)
+            .render(table_a, buf);
+    }
+
+    fn handle_statistics_events(&mut self, key: KeyEvent) {
+        match key.code {
+            _ if key_matches(&self.cfg.keybinds.back, &key) => {
+                self.change_screen(self.last_screen.clone())
+            }
+            _ if key_matches(&self.cfg.keybinds.browse_previous, &key) => {
+                self.cycle_stats_label(false)
+            }
+            _ if key_matches(&self.cfg.keybinds.browse_next, &key) => self.cycle_stats_label(true),
+            _ if key_matches(&self.cfg.keybinds.export_report, &key) => {
+                let p = results::dir().join("report.md");
+                let records = self.filtered_history().collect::<Vec<&Record>>();
+                let (pri, msg) = match results::export_markdown(&p, &records, self.cfg.stats.unit) {
+                    Ok(()) => (
+                        Priority::Info,
+                        format!("Exported report to {}", p.display()),
+                    ),
+                    Err(e) => (Priority::Error, format!("Error exporting report: {e}")),
+                };
+                self.status.push(pri, msg, TimeDelta::seconds(5));
+            }
+            _ => {}
+        }
+    }
+
+    /// History records matching the current label filter
+    fn filtered_history(&self) -> impl Iterator<Item = &Record> {
+        self.history
+            .iter()
+            .filter(|r| self.stats_label.as_ref().is_none_or(|l| &r.label == l))
+    }
+
+    /// Select the next or previous label in history as filter, passing through "all labels"
+    fn cycle_stats_label(&mut self, forward: bool) {
+        let mut labels = self
+            .history
+            .iter()
+            .map(|r| r.label.clone())
+            .collect::<Vec<String>>();
+        labels.sort();
+        labels.dedup();
+
+        // index 0 is "all labels"
+        let n = labels.len() + 1;
+        let cur = match &self.stats_label {
+            Some(l) => labels.iter().position(|x| x == l).map_or(0, |i| i + 1),
+            None => 0,
+        };
+        let next = if forward {
+            (cur + 1) % n
+        } else {
+            (cur + n - 1) % n
+        };
+        self.stats_label = next.checked_sub(1).map(|i| labels[i].clone());
+    }
+
+    /// Sample of the test UI under the previewed built-in theme
+    fn render_themes(&self, area: Rect, buf: &mut Buffer) {
+        let themes = ThemeCfg::builtin();
+        let (name, theme) = &themes[self.theme_i];
+        let sty = Styles::of_theme(theme, self.cfg.ui.caret);
+
+        Block::new().style(sty.root).render(area, buf);
+        Paragraph::new(vec![
+            Line::from(vec![
+                Span::raw("Theme: "),
+                Span::raw(*name).style(sty.accent),
+                Span::raw(format!(
+                    " ({}/{}) {}/{} to browse, {} to apply, {} to go back",
+                    self.theme_i + 1,
+                    themes.len(),
+                    key_names(&self.cfg.keybinds.browse_previous),
+                    key_names(&self.cfg.keybinds.browse_next),
+                    key_names(&self.cfg.keybinds.confirm),
+                    key_names(&self.cfg.keybinds.back)
+                )),
+            ]),
+            Line::raw(""),
+            Line::from(vec![
+                Span::raw("the qu").style(sty.typed),
+                Span::raw("o").style(sty.incorrect),
+                Span::raw("ck ").style(sty.typed),
+                Span::raw("b").style(sty.cursor),
+                Span::raw("rown fox jumps").style(sty.untyped),
+            ]),
+            Line::raw(""),
+            Line::from(vec![
+                Span::raw("arstyper "),
+                Span::raw("Testing").style(sty.modeline_inv),
+            ])
+            .style(sty.modeline),
+            Line::raw("Welcome to arstyper!"),
+        ])
+        .style(sty.root)
+        .block(Block::new().padding(Padding::horizontal(1)))
+        .render(area, buf);
+    }
+
+    fn handle_theme_events(&mut self, key: KeyEvent) {
+        let themes = ThemeCfg::builtin();
+        let kb = &self.cfg.keybinds;
+        let bound = |spec: &str| key_matches(spec, &key);
+        match key.code {
+            _ if bound(&kb.browse_previous) => {
+                self.theme_i = (self.theme_i + themes.len() - 1) % themes.len()
+            }
+            _ if bound(&kb.browse_next) => self.theme_i = (self.theme_i + 1) % themes.len(),
+            _ if bound(&kb.confirm) => {
+                let (name, theme) = themes.into_iter().nth(self.theme_i).unwrap();
+                self.cfg.theme = theme;
+                self.styles = Styles::new(&self.cfg);
+                self.test.set_styles(self.styles.clone());
+                self.status.push(
+                    Priority::Info,
+                    format!("Applied theme {name} for this session."),
+                    TimeDelta::seconds(3),
+                );
+            }
+            _ if key_matches(&self.cfg.keybinds.back, &key) => {
+                self.change_screen(self.last_screen.clone())
+            }
+            _ => {}
+        }
+    }
+
+    fn render_calibration(&self, area: Rect, buf: &mut Buffer) {
+        let unit = self.cfg.stats.unit;
+        let mut lines = vec![Line::raw("Calibration complete!").style(self.styles.accent)];
+        if let Some(c) = &self.calibration
+            && let Some(b) = c.baseline()
+        {
+            lines.extend([
+                Line::raw(format!("Tests: {}", c.progress().1)),
+                Line::raw(format!("Baseline speed: {:.1} {unit}", unit.of_wpm(b.wpm))),
+                Line::raw(format!("Accuracy: {:.1}%", b.accuracy)),
+            ]);
+        }
+        if let Some(g) = &self.goal {
+            lines.push(Line::raw(format!(
+                "Current goal: {:.1} {unit}",
+                unit.of_wpm(g.wpm)
+            )));
+        }
+        lines.extend([
+            Line::raw(""),
+            Line::raw(format!(
+                "Press {} to set this as your goal, or {} to go back.",
+                key_names(&self.cfg.keybinds.confirm),
+                key_names(&self.cfg.keybinds.back)
+            )),
+        ]);
+        Paragraph::new(lines)
+            .style(self.styles.root)
+            .render(area, buf);
+    }
+
+    fn handle_calibration_events(&mut self, key: KeyEvent) {
+        match key.code {
+            _ if key_matches(&self.cfg.keybinds.confirm, &key) => {
+                let Some(b) = self.calibration.take().and_then(|c| c.baseline()) else {
+                    return;
+                };
+                match b.save() {
+                    Ok(()) => self.status.push(
+                        Priority::Info,
+                        "Goal set.".to_string(),
+                        TimeDelta::seconds(3),
+                    ),
+                    Err(e) => self.status.push(
+                        Priority::Error,
+                        format!("Error saving goal: {e}"),
+                        TimeDelta::seconds(5),
+                    ),
+                }
+                self.goal = Some(b);
+            }
+            _ if key_matches(&self.cfg.keybinds.back, &key) => self.calibration = None,
+            _ => return,
+        }
+        self.new_test();
+        self.change_screen(Screen::Test);
     }
 
     fn render_about(&self, area: Rect, buf: &mut Buffer) {
@@ -210,8 +1919,8 @@ impl Ui<'_> {
 
     fn handle_about_events(&mut self, key: KeyEvent) {
         match key.code {
-            KeyCode::Esc | KeyCode::Char('q') => {
-                self.clear_status();
+            _ if key_matches(&self.cfg.keybinds.back, &key) => {
+                self.status.dismiss();
                 self.change_screen(self.last_screen.clone());
             }
             _ => {}
@@ -222,16 +1931,54 @@ impl Ui<'_> {
         let [c1, time_a] =
             Layout::horizontal([Constraint::Min(0), Constraint::Length(8)]).areas(area);
 
-        let mode = format!("{}", self.screen);
-        Line::from(vec![
+        let mode = if matches!(self.screen, Screen::Test) && self.test.is_endless() {
+            "Zen".to_string()
+        } else {
+            format!("{}", self.screen)
+        };
+        let mut segments = vec![
             Span::raw("arstyper "),
             Span::raw(mode).style(self.styles.modeline_inv),
-        ])
-        .style(self.styles.modeline)
-        .render(c1, buf);
+        ];
+        if let Some((lit, _)) = self.metronome_state() {
+            segments.push(Span::raw(" "));
+            segments.push(Span::raw("♩").style(if lit {
+                self.styles.modeline_inv
+            } else {
+                self.styles.modeline
+            }));
+        }
+        if matches!(self.screen, Screen::Test) {
+            if self.test.is_endless() {
+                // over the words still kept, so it follows recent typing
+                segments.push(Span::raw(format!(" {:.0} WPM", self.test.wpm())));
+            } else {
+                let (done, total) = self.test.progress();
+                segments.push(Span::raw(format!(" {done}/{total}")));
+            }
+        }
+        if self.cfg.ui.quiet_errors && matches!(self.screen, Screen::Test) {
+            segments.push(Span::raw(format!(" errors: {}", self.test.errors())));
+        }
+        if self.cfg.ui.error_ticker && matches!(self.screen, Screen::Test) {
+            for (expected, got) in self.test.recent_errors() {
+                // past the end of a word nothing was expected
+                let expected = expected.map_or("·".to_string(), |c| c.to_string());
+                segments.push(Span::raw(" "));
+                segments
+                    .push(Span::raw(format!("{expected}→{got}")).style(self.styles.modeline_inv));
+            }
+        }
+        Line::from(segments)
+            .style(self.styles.modeline)
+            .render(c1, buf);
 
         let time = if self.cfg.ui.show_clock {
-            let t = Local::now();
+            let t = if self.cfg.ui.lazy_clock {
+                self.clock
+            } else {
+                Local::now()
+            };
             format!(
                 "{:02}:{:02}:{:02}",
                 if self.cfg.ui.hour_24 {
@@ -250,42 +1997,440 @@ impl Ui<'_> {
             .render(time_a, buf);
     }
 
-    fn render_status(&self, area: Rect, buf: &mut Buffer) {
-        Line::raw(&self.status)
-            .style(self.styles.root)
-            .render(area, buf);
+    /// Whether the metronome is lit and how long until that changes, None when it is off.
+    /// Beats are counted from the start of the test, or from when it was shown until then
+    fn metronome_state(&self) -> Option<(bool, Duration)> {
+        let bpm = self.cfg.ui.metronome_bpm;
+        if bpm == 0 || !matches!(self.screen, Screen::Test) || self.test.is_completed() {
+            return None;
+        }
+        let start = self.test.started_at().unwrap_or(self.screen_changed);
+        Some(metronome(start.elapsed(), bpm))
     }
 
-    fn set_status_for(&mut self, s: String, t: TimeDelta) {
-        self.status = s;
-        self.clear_status_at = Local::now() + t;
+    /// Progress of the results screen's reveal animation from 0 to 1, None when not animating.
+    /// Any key on the results screen clears `results_shown`, skipping it
+    fn results_reveal(&self) -> Option<f64> {
+        if !self.cfg.ui.animations || !matches!(self.screen, Screen::Results) {
+            return None;
+        }
+        let elapsed = self.results_shown?.elapsed();
+        (elapsed < RESULTS_ANIMATION)
+            .then(|| elapsed.as_secs_f64() / RESULTS_ANIMATION.as_secs_f64())
     }
 
-    fn clear_status(&mut self) {
-        self.status = " ".to_string(); // such that background color can be preserved
-        self.clear_status_at = DateTime::<Local>::MAX_UTC.into()
+    fn start_idling(&mut self) {
+        match results::load() {
+            Ok(h) => {
+                self.history = h;
+                self.idling = true;
+            }
+            Err(e) => {
+                // don't retry until idle again
+                self.last_input = Instant::now();
+                self.status.push(
+                    Priority::Error,
+                    format!("Error reading history: {e}"),
+                    TimeDelta::seconds(5),
+                );
+            }
+        }
+    }
+
+    /// Idle "screensaver", rotating through statistics from history
+    fn render_idle(&self, area: Rect, buf: &mut Buffer) {
+        let unit = self.cfg.stats.unit;
+        let records = self.history.iter().collect::<Vec<&Record>>();
+        let lines = match Summary::of(&records, unit) {
+            Some(s) => vec![
+                format!("Tests recorded: {}", s.tests),
+                format!("Average speed: {:.1} {unit}", s.avg_speed),
+                format!(
+                    "Best speed: {} ({})",
+                    unit.format(s.best.correct_chars, s.best.secs),
+                    s.best.lang
+                ),
+                format!("Time typing: {}m", s.secs as u64 / 60),
+            ],
+            None => vec!["No tests recorded yet.".to_string()],
+        };
+        let i = (self.last_input.elapsed().as_secs() / IDLE_ROTATE_SECS) as usize % lines.len();
+
+        let [_, mid, _] = Layout::vertical([
+            Constraint::Fill(1),
+            Constraint::Length(3),
+            Constraint::Fill(1),
+        ])
+        .areas(area);
+        Paragraph::new(vec![
+            Line::raw("arstyper").style(self.styles.accent),
+            Line::raw(""),
+            Line::raw(lines[i].clone()),
+        ])
+        .centered()
+        .style(self.styles.root)
+        .render(mid, buf);
+    }
+
+    fn render_body(&self, area: Rect, buf: &mut Buffer) {
+        if self.idling {
+            Block::new().style(self.styles.root).render(area, buf);
+            self.render_idle(area, buf);
+            return;
+        }
+        match self.screen {
+            Screen::Test => self.test.render(area, buf),
+            Screen::Results => match self.results_reveal() {
+                Some(t) => {
+                    // slide up from a few rows down, dimmed for the first half
+                    let offset = ((1.0 - t) * RESULTS_SLIDE_ROWS).round() as u16;
+                    let mut a = area;
+                    a.y += offset.min(a.height);
+                    a.height -= offset.min(a.height);
+                    Block::new().style(self.styles.root).render(area, buf);
+                    self.render_results(a, buf);
+                    if t < 0.5 {
+                        buf.set_style(a, Style::new().add_modifier(Modifier::DIM));
+                    }
+                }
+                None => self.render_results(area, buf),
+            },
+            Screen::Statistics => self.render_statistics(area, buf),
+            Screen::About => self.render_about(area, buf),
+            Screen::Theme => self.render_themes(area, buf),
+            Screen::Calibration => self.render_calibration(area, buf),
+        }
+    }
+
+    fn render_status(&self, area: Rect, buf: &mut Buffer) {
+        if self.cfg.ui.show_hints && self.status.text().is_none() {
+            let hints = self
+                .screen
+                .hints(&self.cfg.keybinds)
+                .iter()
+                .map(|(k, action)| format!("{k}: {action}"))
+                .collect::<Vec<String>>();
+            Line::raw(hints.join(" · "))
+                .style(self.styles.root.add_modifier(Modifier::DIM))
+                .render(area, buf);
+            return;
+        }
+        Line::raw(self.status.text().unwrap_or(" ")) // such that background color can be preserved
+            .style(self.styles.root)
+            .render(area, buf);
     }
 
     fn change_screen(&mut self, s: Screen) {
+        if matches!(s, Screen::Statistics) {
+            match results::load() {
+                Ok(h) => self.history = h,
+                Err(e) => self.status.push(
+                    Priority::Error,
+                    format!("Error reading history: {e}"),
+                    TimeDelta::seconds(5),
+                ),
+            }
+        }
+        self.results_shown = matches!(s, Screen::Results).then(Instant::now);
+        self.screen_changed = Instant::now();
+        self.clock = Local::now();
         self.last_screen = self.screen.clone();
         self.screen = s;
     }
 }
 
+/// Keys of a keybind spec for messages, such as `<Esc> or <q>`
+fn key_names(spec: &str) -> String {
+    spec.split_whitespace()
+        .map(|k| format!("<{k}>"))
+        .collect::<Vec<String>>()
+        .join(" or ")
+}
+
+/// Whether a metronome at `bpm` is lit `elapsed` after its first beat, and how long until that changes.
+/// Computed from elapsed time alone so beats don't drift with redraws.
+fn metronome(elapsed: Duration, bpm: u32) -> (bool, Duration) {
+    let beat = Duration::from_secs(60) / bpm;
+    let flash = METRONOME_FLASH.min(beat / 2);
+    let phase = Duration::from_nanos((elapsed.as_nanos() % beat.as_nanos()) as u64);
+    if phase < flash {
+        (true, flash - phase)
+    } else {
+        (false, beat - phase)
+    }
+}
+
+/// Count speeds into equally wide buckets, labelled by their lower bound, from slowest to fastest.
+/// Buckets are 10 wide, or wider multiples of 10 to keep within `HISTOGRAM_MAX_BUCKETS`.
+fn histogram(speeds: &[f64]) -> Vec<(String, u64)> {
+    if speeds.is_empty() {
+        return Vec::new();
+    }
+    let lo = speeds.iter().copied().fold(f64::INFINITY, f64::min);
+    let hi = speeds.iter().copied().fold(0.0, f64::max);
+    let (lo, hi) = ((lo / 10.0) as usize, (hi / 10.0) as usize);
+    let width = (hi - lo + 1).div_ceil(HISTOGRAM_MAX_BUCKETS);
+    let lo = lo / width * width;
+
+    let mut counts = vec![0; (hi - lo) / width + 1];
+    for s in speeds {
+        counts[((*s / 10.0) as usize - lo) / width] += 1;
+    }
+    counts
+        .into_iter()
+        .enumerate()
+        .map(|(i, n)| (((lo + i * width) * 10).to_string(), n))
+        .collect()
+}
+
 impl Widget for &Ui<'_> {
     fn render(self, area: Rect, buf: &mut Buffer) {
         use Constraint::{Length, Min};
-        let vertical = Layout::vertical([Min(0), Length(1), Length(1)]);
-        let [body_a, mode_a, status_a] = vertical.areas(area);
+        let m = self.cfg.ui.margin;
+        let area = if m > 0 {
+            Block::new().style(self.styles.root).render(area, buf);
+            area.inner(Margin::new(m, m))
+        } else {
+            area
+        };
 
-        match self.screen {
-            Screen::TestScreen => self.test.render(body_a, buf),
-            Screen::ResultsScreen => self.render_results(body_a, buf),
-            Screen::StatisticsScreen => self.render_statistics(body_a, buf),
-            Screen::AboutScreen => self.render_about(body_a, buf),
+        if self.cfg.ui.minimal {
+            self.render_body(area, buf);
+
+            // statuses overlay the last line of the body while they last
+            if self.status.text().is_some() {
+                let [_, status_a] = Layout::vertical([Min(0), Length(1)]).areas(area);
+                Clear.render(status_a, buf);
+                self.render_status(status_a, buf);
+            }
+            return;
         }
 
+        let vertical = Layout::vertical([Min(0), Length(1), Length(1)]);
+        let [body_a, mode_a, status_a] = vertical.areas(area);
+
+        self.render_body(body_a, buf);
         self.render_modeline(mode_a, buf);
         self.render_status(status_a, buf);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::crossterm::event::{KeyCode, KeyModifiers};
+
+    #[test]
+    fn test_caret_cycles() {
+        let c = CaretStyle::Block.next();
+        assert!(matches!(c, CaretStyle::Bar));
+        let c = c.next();
+        assert!(matches!(c, CaretStyle::Underline));
+        assert!(matches!(c.next(), CaretStyle::Block));
+    }
+
+    #[test]
+    fn test_margin() {
+        let mut cfg = Config::default();
+        cfg.ui.margin = 1;
+        let ui = Ui::with_words(cfg, vec!["hi".to_string()]);
+        let mut buf = Buffer::empty(Rect::new(0, 0, 20, 5));
+        (&ui).render(buf.area, &mut buf);
+
+        // modeline is inset from the left and sits above the status line, above the bottom margin
+        assert_eq!(buf[(0, 2)].symbol(), " ");
+        assert_eq!(buf[(1, 2)].symbol(), "a");
+        assert_eq!(buf[(1, 4)].symbol(), " ");
+    }
+
+    #[test]
+    fn test_metronome() {
+        let ms = Duration::from_millis;
+        // 120 bpm beats every 500ms
+        assert_eq!(metronome(ms(0), 120), (true, ms(100)));
+        assert_eq!(metronome(ms(250), 120), (false, ms(250)));
+        assert_eq!(metronome(ms(10_520), 120), (true, ms(80)));
+        // flashes are at most half a beat
+        assert_eq!(metronome(ms(10), 1200), (true, ms(15)));
+        assert_eq!(metronome(ms(30), 1200), (false, ms(20)));
+    }
+
+    #[test]
+    fn test_histogram() {
+        assert!(histogram(&[]).is_empty());
+        assert_eq!(
+            histogram(&[42.0, 47.5, 55.0, 71.0]),
+            vec![
+                ("40".to_string(), 2),
+                ("50".to_string(), 1),
+                ("60".to_string(), 0),
+                ("70".to_string(), 1),
+            ]
+        );
+        // too many buckets of 10, so they widen
+        let h = histogram(&[5.0, 300.0]);
+        assert!(h.len() <= HISTOGRAM_MAX_BUCKETS);
+        assert_eq!(h.iter().map(|(_, n)| n).sum::<u64>(), 2);
+    }
+
+    #[test]
+    fn test_results_widgets() {
+        assert!(
+            Config::default()
+                .results
+                .widgets
+                .iter()
+                .all(|w| w.parse::<ResultsWidget>().is_ok())
+        );
+        assert!("graph".parse::<ResultsWidget>().is_err());
+    }
+
+    #[test]
+    fn test_cycle_screens() {
+        let mut s = Screen::Test;
+        let mut seen = Vec::new();
+        for _ in 0..Screen::CYCLE.len() {
+            s = s.cycle(true);
+            seen.push(s.to_string());
+        }
+        assert_eq!(seen, ["Results", "Statistics", "About", "Testing"]);
+        assert!(matches!(Screen::Test.cycle(false), Screen::About));
+        // from outside the cycle back into it
+        assert!(matches!(Screen::Theme.cycle(true), Screen::Test));
+    }
+
+    #[test]
+    fn test_results_reveal() {
+        let mut cfg = Config::default();
+        cfg.ui.set_terminal_title = false;
+        let mut ui = Ui::with_words(cfg, vec!["hi".to_string()]);
+        ui.change_screen(Screen::Results);
+        assert_eq!(ui.results_reveal(), None);
+
+        ui.cfg.ui.animations = true;
+        ui.change_screen(Screen::Results);
+        assert!(ui.results_reveal().is_some_and(|t| t < 1.0));
+        // any key skips it
+        ui.handle_results_events(KeyEvent::from(KeyCode::Char('x')));
+        assert_eq!(ui.results_reveal(), None);
+    }
+
+    #[test]
+    fn test_receive_requests() {
+        let mut cfg = Config::default();
+        cfg.ui.set_terminal_title = false;
+        let mut ui = Ui::with_words(cfg, vec!["hi".to_string()]);
+        for msg in [
+            UiRequest::ChangeScreen(Screen::Results),
+            UiRequest::DisplayStatus(Priority::Info, "hi".to_string(), TimeDelta::seconds(1)),
+            UiRequest::NewTest,
+        ] {
+            ui.uireq_tx.try_send(msg).unwrap();
+        }
+        ui.receive_requests();
+        // a request waiting on the screen isn't replaced by a later one
+        assert!(matches!(
+            ui.deferred.iter().collect::<Vec<_>>()[..],
+            [UiRequest::ChangeScreen(Screen::Results), UiRequest::NewTest]
+        ));
+
+        // keys on a frozen test ask for results again and again
+        ui.deferred.clear();
+        for _ in 0..3 {
+            ui.uireq_tx
+                .try_send(UiRequest::ChangeScreen(Screen::Results))
+                .unwrap();
+        }
+        ui.receive_requests();
+        assert_eq!(ui.deferred.len(), 1);
+    }
+
+    #[test]
+    fn test_record_once() {
+        let mut cfg = Config::default();
+        cfg.ui.set_terminal_title = false;
+        let mut ui = Ui::with_words(cfg, vec!["hi".to_string()]);
+        ui.new_test();
+        ui.record_test();
+        ui.record_test();
+        assert_eq!(ui.completed_tests, 1);
+        ui.new_test();
+        ui.record_test();
+        assert_eq!(ui.completed_tests, 2);
+    }
+
+    #[test]
+    fn test_inorder_place() {
+        let mut cfg = Config::default();
+        cfg.ui.set_terminal_title = false;
+        cfg.word_count = 2;
+        let mut l = Lang::from_words("ui-inorder", ["a", "b", "c"].map(String::from).to_vec());
+        l.inorder = true;
+        let mut ui = Ui::with_lang(cfg, l, None);
+        ui.new_test();
+        ui.test.handle_events(KeyEvent::from(KeyCode::Char('a')));
+        // a restart brings back the words that weren't recorded
+        ui.new_test();
+        assert_eq!(ui.test.words().collect::<Vec<&str>>(), vec!["a", "b"]);
+        for c in "a b".chars() {
+            ui.test.handle_events(KeyEvent::from(KeyCode::Char(c)));
+        }
+        ui.record_test();
+        ui.new_test();
+        assert_eq!(ui.test.words().collect::<Vec<&str>>(), vec!["c", "a"]);
+    }
+
+    #[test]
+    fn test_word_count_preset() {
+        let mut cfg = Config::default();
+        cfg.ui.set_terminal_title = false;
+        let l = Lang::from_words("ui-presets", ["a", "b", "c"].map(String::from).to_vec());
+        let mut ui = Ui::with_lang(cfg, l, None);
+        ui.new_test();
+        ui.handle_key(KeyEvent::new(KeyCode::Char('2'), KeyModifiers::ALT));
+        assert_eq!(ui.cfg.word_count, 25);
+        assert_eq!(ui.test.word_count(), 25);
+    }
+
+    #[test]
+    fn test_leave_frozen_test() {
+        let mut cfg = Config::default();
+        cfg.ui.set_terminal_title = false;
+        cfg.test.freeze_on_complete = true;
+        let mut ui = Ui::with_words(cfg, vec!["hi".to_string()]);
+        ui.new_test();
+        for c in "hi ".chars() {
+            ui.test.handle_events(KeyEvent::from(KeyCode::Char(c)));
+        }
+        assert!(ui.test.is_completed());
+        // away from the frozen test and back, without seeing results
+        ui.cycle_screen(false);
+        ui.cycle_screen(true);
+        assert!(!ui.test.is_completed());
+        assert_eq!(ui.completed_tests, 1);
+    }
+
+    #[test]
+    fn test_hints() {
+        use strum::IntoEnumIterator;
+        let kb = KeybindsCfg::default();
+        assert!(Screen::iter().all(|s| !s.hints(&kb).is_empty()));
+        let kb = KeybindsCfg {
+            help: "".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(Screen::Test.hints(&kb).last(), Some(&("Ctrl+R", "restart")));
+    }
+
+    #[test]
+    fn test_with_words() {
+        let mut cfg = Config::default();
+        cfg.ui.set_terminal_title = false;
+        let mut ui = Ui::with_words(cfg, vec!["Hello".to_string(), "there".to_string()]);
+        ui.new_test();
+        assert_eq!(
+            ui.test.words().collect::<Vec<&str>>(),
+            vec!["hello", "there"]
+        );
+    }
+}